@@ -0,0 +1,196 @@
+/*
+ *  arcamclient —  A gtk-rs based Rust application for controlling Arcam amplifiers.
+ *
+ *  Copyright © 2020  Russel Winder
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! This module provides the [Model](enum.Model.html) type that records what each model of
+//! Arcam amplifier can do: the [Command](../enum.Command.html)s and the
+//! [Source](../enum.Source.html)s it supports, the port it listens on, and how fast it can be
+//! sent requests. All models are controlled in zones 1 and 2, see
+//! [ZoneNumber](../enum.ZoneNumber.html).
+//!
+//! The AVR450 and AVR750 are covered by the same protocol documentation as the AVR850, the
+//! only difference noted being that they have a DAB tuner source. The AVR600 uses an earlier
+//! version of the protocol, see [Dialect](../enum.Dialect.html).
+
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::{AmpCommand, Command, DeviceInfo, Dialect, ProtocolError, Request, Source};
+
+use strum_macros::{Display, EnumString};
+
+/// The models of Arcam amplifier that are known about.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, Eq, PartialEq)]
+pub enum Model {
+    AVR450,
+    AVR600,
+    AVR750,
+    #[default]
+    AVR850,
+}
+
+static AVR850_SOURCES: &[Source] = &[
+    Source::FollowZone1, Source::CD, Source::BD, Source::AV, Source::SAT, Source::PVR, Source::VCR, Source::AUX,
+    Source::DISPLAY, Source::TUNER, Source::NET, Source::USB, Source::STB, Source::GAME,
+];
+
+static AVR450_SOURCES: &[Source] = &[
+    Source::FollowZone1, Source::CD, Source::BD, Source::AV, Source::SAT, Source::PVR, Source::VCR, Source::AUX,
+    Source::DISPLAY, Source::TUNER, Source::TUNERDAB, Source::NET, Source::USB, Source::STB, Source::GAME,
+];
+
+impl Model {
+    /// The model given by the `Device-Model` field of an AMX identification reply, if it is
+    /// a known model.
+    pub fn from_device_info(device: &DeviceInfo) -> Option<Self> {
+        device.model.parse().ok()
+    }
+
+    /// The TCP port the amplifier listens on.
+    pub fn default_port(self: &Self) -> u16 {
+        match self {
+            Self::AVR600 => 50001,
+            _ => 50000,
+        }
+    }
+
+    /// The smallest time between requests that the amplifier reliably deals with.
+    ///
+    /// The gap for an AVR850 was ascertained by rough experiment rather than guesswork: 150 ms
+    /// seems insufficient, 175 ms works sometimes, 200 ms seems mostly to work but not always,
+    /// 225 ms seems to work always. The other models are assumed to be the same.
+    pub fn minimum_request_gap(self: &Self) -> Duration {
+        match self {
+            Self::AVR450 | Self::AVR600 | Self::AVR750 | Self::AVR850 => Duration::from_millis(225),
+        }
+    }
+
+    /// The sources that can be selected on the amplifier.
+    ///
+    /// The AVR600 protocol documentation does not list the source values, the AVR600 is assumed
+    /// to have the sources of the AVR850 since the commands selecting them are common to both.
+    pub fn sources(self: &Self) -> &'static [Source] {
+        match self {
+            Self::AVR450 | Self::AVR750 => AVR450_SOURCES,
+            Self::AVR850 => AVR850_SOURCES,
+            // An assumption, see above.
+            Self::AVR600 => AVR850_SOURCES,
+        }
+    }
+
+    /// Can the source be selected on the amplifier.
+    pub fn supports_source(self: &Self, source: Source) -> bool {
+        self.sources().contains(&source)
+    }
+
+    /// The [Dialect](../enum.Dialect.html) of the protocol the amplifier uses.
+    pub fn dialect(self: &Self) -> Dialect {
+        match self {
+            Self::AVR600 => Dialect::AVR600,
+            Self::AVR450 | Self::AVR750 | Self::AVR850 => Dialect::AVR850,
+        }
+    }
+
+    /// Does the amplifier understand the command.
+    pub fn supports_command(self: &Self, cc: Command) -> bool {
+        self.dialect().supports_command(cc)
+    }
+
+    /// Check that the amplifier can handle a [Request](../struct.Request.html).
+    pub fn check_request(self: &Self, request: &Request) -> Result<(), ProtocolError> {
+        if !self.supports_command(request.cc) { return Err(ProtocolError::UnsupportedCommand(request.cc)); }
+        request.check_zone()
+    }
+
+    /// Return the sequence of [Request](../struct.Request.html)s that carry out
+    /// an [AmpCommand](../enum.AmpCommand.html) on this model of amplifier, or
+    /// an error if the amplifier cannot handle the command.
+    pub fn to_requests(self: &Self, command: &AmpCommand) -> Result<Vec<Request>, ProtocolError> {
+        if let AmpCommand::SelectSource(_, source) = command {
+            if !self.supports_source(*source) { return Err(ProtocolError::UnsupportedSource(*source)); }
+        }
+        let requests = command.to_requests()?;
+        for request in requests.iter() {
+            self.check_request(request)?;
+        }
+        Ok(requests)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    use crate::ZoneNumber;
+
+    #[test]
+    fn model_from_device_info() {
+        let mut device = DeviceInfo {
+            sdk_class: "Receiver".to_string(),
+            make: "ARCAM".to_string(),
+            model: "AVR850".to_string(),
+            revision: "2.0.0".to_string(),
+        };
+        assert_eq!(Model::from_device_info(&device), Some(Model::AVR850));
+        device.model = "SA30".to_string();
+        assert_eq!(Model::from_device_info(&device), None);
+    }
+
+    #[test]
+    fn avr850_needs_225ms_between_requests() {
+        assert_eq!(Model::AVR850.minimum_request_gap(), Duration::from_millis(225));
+    }
+
+    #[test]
+    fn default_ports() {
+        assert_eq!(Model::AVR850.default_port(), 50000);
+        assert_eq!(Model::AVR600.default_port(), 50001);
+    }
+
+    #[test]
+    fn avr600_refuses_avr850_only_commands() {
+        assert_eq!(
+            Model::AVR600.to_requests(&AmpCommand::Query(ZoneNumber::One, Command::Heartbeat)),
+            Err(ProtocolError::UnsupportedCommand(Command::Heartbeat))
+        );
+        assert!(Model::AVR850.to_requests(&AmpCommand::Query(ZoneNumber::One, Command::Heartbeat)).is_ok());
+        assert_eq!(
+            Model::AVR850.to_requests(&AmpCommand::Query(ZoneNumber::One, Command::SetRequestOutputFrameRate)),
+            Err(ProtocolError::UnsupportedCommand(Command::SetRequestOutputFrameRate))
+        );
+    }
+
+    #[test]
+    fn zone_1_only_commands_are_refused_for_zone_2() {
+        assert_eq!(
+            Model::AVR850.to_requests(&AmpCommand::Query(ZoneNumber::Two, Command::DisplayBrightness)),
+            Err(ProtocolError::CommandNotForZone(Command::DisplayBrightness, ZoneNumber::Two))
+        );
+        assert!(Model::AVR850.to_requests(&AmpCommand::Query(ZoneNumber::One, Command::DisplayBrightness)).is_ok());
+    }
+
+    #[test]
+    fn only_some_models_have_a_dab_source() {
+        assert_eq!(
+            Model::AVR850.to_requests(&AmpCommand::SelectSource(ZoneNumber::One, Source::TUNERDAB)),
+            Err(ProtocolError::UnsupportedSource(Source::TUNERDAB))
+        );
+        assert!(Model::AVR750.to_requests(&AmpCommand::SelectSource(ZoneNumber::One, Source::TUNERDAB)).is_ok());
+    }
+}
@@ -0,0 +1,174 @@
+/*
+ *  arcamclient —  A gtk-rs based Rust application for controlling Arcam amplifiers.
+ *
+ *  Copyright © 2020  Russel Winder
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Decoding of the text sent by broadcasters, as relayed by the amplifier in responses such as
+//! those to `RequestDABStation`, `ProgrammeTypeCategory` and `DLSPDTInformation`.
+//!
+//! DAB and RDS broadcasts use the EBU Latin based character set of ETSI TS 101 756 rather
+//! than UTF-8, though some DAB broadcasters do send UTF-8. There is no indication in the
+//! packet of which is being used, so data that is valid UTF-8 is taken as UTF-8 and anything
+//! else as EBU Latin: the accented characters of EBU Latin are very rarely valid UTF-8.
+//!
+//! Fixed length fields, e.g. the 16 byte station name, are padded with spaces. The AVR850
+//! sends DLS text as a 129 byte buffer with a nul terminated string padded with spaces, and
+//! with a leading control byte, rather than the documented 128 byte space padded buffer.
+
+use alloc::string::{String, ToString};
+
+/// The characters for the bytes 0x80 to 0xFF of the EBU Latin based character set.
+///
+/// Bytes 0x20 to 0x7F are taken to be ASCII: the few EBU Latin characters that differ in that
+/// range are not used by broadcasters in practice.
+const EBU_LATIN_UPPER: [char; 128] = [
+    // 0x80
+    'á', 'à', 'é', 'è', 'í', 'ì', 'ó', 'ò', 'ú', 'ù', 'Ñ', 'Ç', 'Ş', 'ß', '¡', 'Ĳ',
+    // 0x90
+    'â', 'ä', 'ê', 'ë', 'î', 'ï', 'ô', 'ö', 'û', 'ü', 'ñ', 'ç', 'ş', 'ğ', 'ı', 'ĳ',
+    // 0xA0
+    'ª', 'α', '©', '‰', 'Ğ', 'ě', 'ň', 'ő', 'π', '€', '£', '$', '←', '↑', '→', '↓',
+    // 0xB0
+    'º', '¹', '²', '³', '±', 'İ', 'ń', 'ű', 'µ', '¿', '÷', '°', '¼', '½', '¾', '§',
+    // 0xC0
+    'Á', 'À', 'É', 'È', 'Í', 'Ì', 'Ó', 'Ò', 'Ú', 'Ù', 'Ř', 'Č', 'Š', 'Ž', 'Đ', 'Ŀ',
+    // 0xD0
+    'Â', 'Ä', 'Ê', 'Ë', 'Î', 'Ï', 'Ô', 'Ö', 'Û', 'Ü', 'ř', 'č', 'š', 'ž', 'đ', 'ŀ',
+    // 0xE0
+    'Ã', 'Å', 'Æ', 'Œ', 'ŷ', 'Ý', 'Õ', 'Ø', 'Þ', 'Ŋ', 'Ŕ', 'Ć', 'Ś', 'Ź', 'Ŧ', 'ð',
+    // 0xF0
+    'ã', 'å', 'æ', 'œ', 'ŵ', 'ý', 'õ', 'ø', 'þ', 'ŋ', 'ŕ', 'ć', 'ś', 'ź', 'ŧ', 'ħ',
+];
+
+/// The length of the DLS data sent by an AVR850: a control byte followed by 128 bytes of text.
+const DLS_DATA_LENGTH: usize = 129;
+
+/// The character for a byte of EBU Latin text.
+fn ebu_latin_char(byte: u8) -> char {
+    if byte < 0x80 { byte as char } else { EBU_LATIN_UPPER[(byte - 0x80) as usize] }
+}
+
+/// Decode the data of a broadcast text response into a string.
+///
+/// The leading control byte of 129 byte DLS data is dropped. The text ends at the first nul,
+/// if there is one. Control characters are dropped, other than line breaks which become
+/// spaces, and leading and trailing spaces are removed.
+pub fn decode_broadcast_text(data: &[u8]) -> String {
+    let data = if data.len() == DLS_DATA_LENGTH { &data[1..] } else { data };
+    let end = data.iter().position(|x| *x == 0u8).unwrap_or(data.len());
+    let bytes = &data[..end];
+    let text = match core::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => bytes.iter().map(|b| ebu_latin_char(*b)).collect(),
+    };
+    text.chars()
+        .filter_map(|c| match c {
+            '\n' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    /// Strip the start, zone, command, answer code and data length from a captured packet, and
+    /// the end marker if present.
+    fn packet_data(packet: &[u8]) -> &[u8] {
+        let data = &packet[5..];
+        if data.len() > packet[4] as usize { &data[..packet[4] as usize] } else { data }
+    }
+
+    #[test]
+    fn decode_captured_station_name() {
+        let packet = [33, 1, 24, 0, 16, 83, 109, 111, 111, 116, 104, 32, 67, 111, 117, 110, 116, 114, 121, 32, 32, 13];
+        assert_eq!(decode_broadcast_text(packet_data(&packet)), "Smooth Country");
+    }
+
+    #[test]
+    fn decode_captured_programme_type() {
+        let packet = [33, 1, 25, 0, 16, 67, 111, 117, 110, 116, 114, 121, 32, 77, 117, 115, 105, 99, 32, 32, 32, 13];
+        assert_eq!(decode_broadcast_text(packet_data(&packet)), "Country Music");
+    }
+
+    #[test]
+    fn decode_captured_dls_with_double_nul() {
+        let mut packet = vec![33, 1, 26, 0, 129, 12, 79, 110, 32, 65, 105, 114, 32, 78, 111, 119, 32, 111, 110, 32,
+            83, 109, 111, 111, 116, 104, 58, 32, 71, 97, 114, 121, 32, 75, 105, 110, 103, 0, 0];
+        packet.resize(5 + 129, 32);
+        assert_eq!(decode_broadcast_text(packet_data(&packet)), "On Air Now on Smooth: Gary King");
+    }
+
+    #[test]
+    fn decode_captured_dls_on_channel_change() {
+        let mut packet = vec![33, 1, 26, 0, 129, 25, 78, 111, 119, 32, 111, 110, 32, 83, 109, 111, 111, 116, 104, 32, 67,
+            111, 117, 110, 116, 114, 121, 58, 32, 66, 114, 101, 116, 116, 32, 69, 108, 100, 114, 101, 100,
+            103, 101, 32, 119, 105, 116, 104, 32, 68, 114, 117, 110, 107, 32, 79, 110, 32, 89, 111, 117,
+            114, 32, 76, 111, 118, 101, 0, 0];
+        packet.resize(5 + 129, 32);
+        packet.push(13);
+        assert_eq!(
+            decode_broadcast_text(packet_data(&packet)),
+            "Now on Smooth Country: Brett Eldredge with Drunk On Your Love"
+        );
+    }
+
+    #[test]
+    fn decode_dls_drops_printable_control_byte() {
+        let mut data = vec![0x41];
+        data.extend_from_slice(b"On Air Now on Smooth: Gary King\0");
+        data.resize(129, 32);
+        assert_eq!(decode_broadcast_text(&data), "On Air Now on Smooth: Gary King");
+    }
+
+    #[test]
+    fn decode_documented_128_byte_dls() {
+        let mut data = b"Smooth - Your Relaxing Music Mix".to_vec();
+        data.resize(128, 32);
+        assert_eq!(decode_broadcast_text(&data), "Smooth - Your Relaxing Music Mix");
+    }
+
+    #[test]
+    fn decode_ebu_latin_accents() {
+        assert_eq!(decode_broadcast_text(b"Beyonc\x82 & Bj\x97rk      "), "Beyoncé & Björk");
+        assert_eq!(decode_broadcast_text(b"Si\x9Bn \xC0 \xE5"), "Siçn Á Ý");
+    }
+
+    #[test]
+    fn decode_utf8() {
+        assert_eq!(decode_broadcast_text("Beyoncé & Björk      ".as_bytes()), "Beyoncé & Björk");
+    }
+
+    #[test]
+    fn decode_line_break_as_space() {
+        assert_eq!(decode_broadcast_text(b"Now Playing\nGary King"), "Now Playing Gary King");
+    }
+
+    #[test]
+    fn decode_all_ebu_latin_bytes() {
+        let data: Vec<u8> = (0x80..=0xFF).collect();
+        let text = decode_broadcast_text(&data);
+        assert_eq!(text.chars().count(), 128);
+        assert!(text.chars().all(|c| !c.is_control()));
+    }
+}
@@ -0,0 +1,4126 @@
+/*
+ *  arcamclient —  A gtk-rs based Rust application for controlling Arcam amplifiers.
+ *
+ *  Copyright © 2020  Russel Winder
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! This crate provides various enums and structs to do with implementing the Arcam protocol
+//! for communicating with an Arcam amplifier over a TCP connection. It has no GUI or I/O
+//! dependencies so that any controller, not just the GTK application, can use it.
+//!
+//! The crate is `no_std`, it needs only `alloc`. The default `std` feature adds the
+//! implementation of `std::error::Error` for [ProtocolError](enum.ProtocolError.html), and the
+//! `serde` feature adds serialisation of the protocol types.
+//!
+//! The Arcam manual states that each Arcam amplifier that has an Ethernet connection can be
+//! connected to using port 50000 (AVR850, 50001 for AVR600). The amplifier responds to messages
+//! "AMX" by sending an AMXB response. Otherwise communication is via packets with well defined
+//! structures.  Each packet starts with 0x21 (!) and ends with 0x0d (\r).
+//!
+//! Request packets sent to the amplifier are structured:
+//!
+//! - St (Start transmission): 0x21 (‘!’)
+//! - Zn (Zone number): 0x1, 0x2 for the zone number
+//! - Cc (Command code): the code for the command
+//! - Dl (Data Length): the number of data items following this item, excluding the Et
+//! - Data: the parameters for the response of length n. n is limited to 255
+//! - Et (End transmission): 0x0d (\r)
+//!
+//! Response packet sent by the amplifier are structured:
+//!
+//! - St (Start transmission): 0x21 (‘!’)
+//! - Zn (Zone number): 0x1, 0x2 for the zone number
+//! - Cc (Command code): the code for the command
+//! - Ac (Answer code): the answer code for the request
+//! - Dl (Data Length): the number of data items following this item, excluding the Et
+//! - Data: the parameters for the response of length n. n is limited to 255
+//! - Et (End transmission): 0x0d (\r)
+//!
+//! Communication is not synchronous: the amplifier receives request packets and within 3
+//! seconds will send a response packet. There is no guarantee that the response order will be
+//! the request order, but it normal circumstances it probably will be.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod amp_model;
+pub mod broadcast_text;
+
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
+
+#[allow(unused_imports)]  // Compiler misses the use in a derive.
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use strum_macros::{Display, EnumString};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::broadcast_text::decode_broadcast_text;
+
+/// Zone numbers 1 and 2. The AVR600 also has a zone 3, which is not supported.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[repr(u8)]
+pub enum ZoneNumber {
+    One = 1,
+    Two = 2,
+}
+
+impl ZoneNumber {
+    /// All the zones, in numeric order.
+    pub const ALL: [ZoneNumber; 2] = [Self::One, Self::Two];
+}
+
+/// The commands (Cc entries) that can be sent to the amplifier using the message protocol.
+///
+/// The command code (Cc) of a command depends on the [Dialect](enum.Dialect.html) of the
+/// protocol, see [Dialect::code](enum.Dialect.html#method.code).
+#[derive(Clone, Copy, Debug, EnumString, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Command {
+    // =================== System Commands
+    Power,
+    DisplayBrightness,
+    Headphones,
+    FMGenre,
+    SoftwareVersion,
+    RestoreFactoryDefaultSettings,
+    SaveRestoreSecureCopyOfSettings,
+    SimulateRC5IRCommand,
+    DisplayInformationType,
+    RequestCurrentSource,
+    HeadphoneOverride,
+    // =================== Input Commands
+    VideoSelection,
+    SelectAnalogueDigital,
+    SetRequestVideoInputType,
+    // =================== Output Commands
+    SetRequestVolume,
+    RequestMuteStatus,
+    RequestDirectModeStatus,
+    RequestDecodeModeStatus2ch,
+    RequestDecodeModeStatusMCH,
+    RequestRDSInformation,
+    SetRequestVideoOutputResolution,
+    // =================== Menu Commands
+    RequestMenuStatus,
+    RequestTunerPreset,
+    Tune,
+    RequestDABStation,  // Was called RequestDABSiriusStation for AVR600
+    ProgrammeTypeCategory,  // Was called RadioProgrammeTypeCategory for AVR600
+    DLSPDTInformation,  // Was called RequestRDSDLSInformation dor AVR600
+    RequestPresetDetails,
+    NetworkPlaybackStatus,
+    IMAXEnhanced,
+    // =================== Setup Adjustment Commands
+    TrebleEqualisation,
+    BassEqualisation,
+    RoomEqualisation,
+    DolbyVolume,
+    DolbyLeveller,
+    DolbyVolumeCalibrationOffset,
+    Balance,
+    DolbyProLogicIIDimension,
+    DolbyProLogicIICentreWidth,
+    DolbyProLogicIIPanorama,
+    SubwooferTrim,
+    LipsyncDelay,
+    Compression,
+    RequestIncomingVideoParameters,
+    RequestIncomingAudioFormat,
+    RequestIncomingAudioSampleRate,
+    SetRequestSubStereoTrim,
+    SetRequestBrightness,
+    SetRequestContrast,
+    SetRequestColour,
+    SetRequestPictureMode,
+    SetRequestEdgeEnhancement,
+    SetRequestMosquitoNR,
+    SetRequestNoiseReduction,
+    SetRequestBlockNoiseReduction,
+    SetRequestZone1OSDOnOff,
+    SetRequestVideoOutputSwitching,
+    SetRequestOutputFrameRate,
+    SetRequestInputName,
+    FMScanUpDown,
+    DABScan,
+    Heartbeat,
+    Reboot,
+}
+
+/// The command codes (Cc) of the [Command](enum.Command.html)s that are the same in all
+/// [Dialect](enum.Dialect.html)s.
+static COMMON_CODES: &[(Command, u8)] = &[
+    (Command::Power, 0x00), (Command::DisplayBrightness, 0x01), (Command::Headphones, 0x02), (Command::FMGenre, 0x03),
+    (Command::SoftwareVersion, 0x04), (Command::RestoreFactoryDefaultSettings, 0x05),
+    (Command::SaveRestoreSecureCopyOfSettings, 0x06), (Command::SimulateRC5IRCommand, 0x08),
+    (Command::DisplayInformationType, 0x09), (Command::RequestCurrentSource, 0x1D), (Command::HeadphoneOverride, 0x1F),
+    (Command::VideoSelection, 0x0A), (Command::SelectAnalogueDigital, 0x0B), (Command::SetRequestVolume, 0x0D),
+    (Command::RequestMuteStatus, 0x0E), (Command::RequestDirectModeStatus, 0x0F),
+    (Command::RequestDecodeModeStatus2ch, 0x10), (Command::RequestDecodeModeStatusMCH, 0x11),
+    (Command::RequestRDSInformation, 0x12), (Command::SetRequestVideoOutputResolution, 0x13),
+    (Command::RequestMenuStatus, 0x14), (Command::RequestTunerPreset, 0x15), (Command::Tune, 0x16),
+    (Command::RequestDABStation, 0x18), (Command::ProgrammeTypeCategory, 0x19), (Command::DLSPDTInformation, 0x1A),
+    (Command::RequestPresetDetails, 0x1B), (Command::NetworkPlaybackStatus, 0x1C), (Command::TrebleEqualisation, 0x35),
+    (Command::BassEqualisation, 0x36), (Command::RoomEqualisation, 0x37), (Command::DolbyVolume, 0x38),
+    (Command::DolbyLeveller, 0x39), (Command::DolbyVolumeCalibrationOffset, 0x3A), (Command::Balance, 0x3B),
+    (Command::DolbyProLogicIIDimension, 0x3C), (Command::DolbyProLogicIICentreWidth, 0x3D),
+    (Command::DolbyProLogicIIPanorama, 0x3E), (Command::SubwooferTrim, 0x3F), (Command::LipsyncDelay, 0x40),
+    (Command::Compression, 0x41), (Command::RequestIncomingVideoParameters, 0x42),
+    (Command::RequestIncomingAudioFormat, 0x43), (Command::RequestIncomingAudioSampleRate, 0x44),
+    (Command::SetRequestSubStereoTrim, 0x45), (Command::SetRequestZone1OSDOnOff, 0x4E),
+    (Command::SetRequestVideoOutputSwitching, 0x4F),
+];
+
+/// The command codes (Cc) of the [Command](enum.Command.html)s only in the AVR850 dialect.
+static AVR850_CODES: &[(Command, u8)] = &[
+    (Command::IMAXEnhanced, 0x0C), (Command::SetRequestInputName, 0x20), (Command::FMScanUpDown, 0x23),
+    (Command::DABScan, 0x24), (Command::Heartbeat, 0x25), (Command::Reboot, 0x26),
+];
+
+/// The command codes (Cc) of the [Command](enum.Command.html)s only in the AVR600 dialect. The
+/// AVR600 uses 0x0C, the AVR850 code for `IMAXEnhanced`, for `SetRequestVideoInputType`.
+static AVR600_CODES: &[(Command, u8)] = &[
+    (Command::SetRequestVideoInputType, 0x0C), (Command::SetRequestBrightness, 0x46),
+    (Command::SetRequestContrast, 0x47), (Command::SetRequestColour, 0x48), (Command::SetRequestPictureMode, 0x49),
+    (Command::SetRequestEdgeEnhancement, 0x4A), (Command::SetRequestMosquitoNR, 0x4B),
+    (Command::SetRequestNoiseReduction, 0x4C), (Command::SetRequestBlockNoiseReduction, 0x4D),
+    (Command::SetRequestOutputFrameRate, 0x50),
+];
+
+/// The names of [Command](enum.Command.html)s in the AVR600 documentation that differ from
+/// the names in the AVR850 documentation.
+static AVR600_NAMES: &[(Command, &str)] = &[
+    (Command::RequestDABStation, "RequestDABSiriusStation"),
+    (Command::ProgrammeTypeCategory, "RadioProgrammeTypeCategory"),
+    (Command::DLSPDTInformation, "RequestRDSDLSInformation"),
+];
+
+/// The [Command](enum.Command.html)s that apply only to zone 1: those for the display, the
+/// set up, the decoding, and the video processing of the amplifier as a whole.
+static ZONE_1_ONLY_COMMANDS: &[Command] = &[
+    Command::DisplayBrightness, Command::Headphones, Command::SoftwareVersion, Command::RestoreFactoryDefaultSettings,
+    Command::SaveRestoreSecureCopyOfSettings, Command::HeadphoneOverride, Command::VideoSelection,
+    Command::SelectAnalogueDigital, Command::SetRequestVideoInputType, Command::RequestDirectModeStatus,
+    Command::RequestDecodeModeStatus2ch, Command::RequestDecodeModeStatusMCH, Command::SetRequestVideoOutputResolution,
+    Command::RequestMenuStatus, Command::IMAXEnhanced, Command::TrebleEqualisation, Command::BassEqualisation,
+    Command::RoomEqualisation, Command::DolbyVolume, Command::DolbyLeveller, Command::DolbyVolumeCalibrationOffset,
+    Command::Balance, Command::DolbyProLogicIIDimension, Command::DolbyProLogicIICentreWidth,
+    Command::DolbyProLogicIIPanorama, Command::SubwooferTrim, Command::LipsyncDelay, Command::Compression,
+    Command::SetRequestSubStereoTrim, Command::SetRequestBrightness, Command::SetRequestContrast,
+    Command::SetRequestColour, Command::SetRequestPictureMode, Command::SetRequestEdgeEnhancement,
+    Command::SetRequestMosquitoNR, Command::SetRequestNoiseReduction, Command::SetRequestBlockNoiseReduction,
+    Command::SetRequestZone1OSDOnOff, Command::SetRequestVideoOutputSwitching, Command::SetRequestOutputFrameRate,
+    Command::Heartbeat, Command::Reboot,
+];
+
+impl Command {
+    /// The command code (Cc) sent to the amplifier for this command, the code in the
+    /// [Dialect](enum.Dialect.html) that has the command, the AVR850 one if both have it.
+    pub fn code(self: &Self) -> u8 {
+        Dialect::AVR850.code(*self)
+            .or_else(|| Dialect::AVR600.code(*self))
+            .expect("Every command has a code in some dialect.")
+    }
+
+    /// Does the command apply to the zone. Commands for the amplifier as a whole, rather than
+    /// for a zone, apply to zone 1 only.
+    pub fn applies_to_zone(self: &Self, zone: ZoneNumber) -> bool {
+        zone == ZoneNumber::One || !ZONE_1_ONLY_COMMANDS.contains(self)
+    }
+}
+
+/// The variants of the Arcam protocol.
+///
+/// The AVR600 uses an earlier version of the protocol than the AVR850: it has some video
+/// processing commands not in the AVR850, does not have some of the newer commands of the
+/// AVR850, uses 0x0C for a different command, and uses different names for some commands.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, Eq, PartialEq)]
+pub enum Dialect {
+    #[default]
+    AVR850,
+    AVR600,
+}
+
+impl Dialect {
+    /// The tables of command codes (Cc) of this dialect.
+    fn code_tables(self: &Self) -> [&'static [(Command, u8)]; 2] {
+        match self {
+            Self::AVR850 => [COMMON_CODES, AVR850_CODES],
+            Self::AVR600 => [COMMON_CODES, AVR600_CODES],
+        }
+    }
+
+    /// The command code (Cc) of the [Command](enum.Command.html) in this dialect, if the
+    /// command is part of this dialect.
+    pub fn code(self: &Self, cc: Command) -> Option<u8> {
+        self.code_tables().iter().flat_map(|table| table.iter()).find(|(c, _)| *c == cc).map(|(_, code)| *code)
+    }
+
+    /// Is the command part of this dialect.
+    pub fn supports_command(self: &Self, cc: Command) -> bool {
+        self.code(cc).is_some()
+    }
+
+    /// The [Command](enum.Command.html) for a command code (Cc) received from an amplifier
+    /// using this dialect.
+    pub fn command(self: &Self, code: u8) -> Result<Command, ProtocolError> {
+        self.code_tables().iter()
+            .flat_map(|table| table.iter())
+            .find(|(_, c)| *c == code)
+            .map(|(cc, _)| *cc)
+            .ok_or(ProtocolError::UnknownCommand(code))
+    }
+
+    /// The name of the [Command](enum.Command.html) as used in the documentation for this
+    /// dialect.
+    pub fn command_name(self: &Self, cc: Command) -> String {
+        match self {
+            Self::AVR600 => AVR600_NAMES.iter().find(|(c, _)| *c == cc).map(|(_, name)| name.to_string()),
+            Self::AVR850 => None,
+        }.unwrap_or_else(|| format!("{:?}", cc))
+    }
+
+    /// The [Command](enum.Command.html) with the given name in this dialect. The AVR850 names
+    /// are accepted in all dialects.
+    pub fn parse_command_name(self: &Self, name: &str) -> Option<Command> {
+        let cc = match self {
+            Self::AVR600 => AVR600_NAMES.iter().find(|(_, n)| *n == name).map(|(cc, _)| *cc),
+            Self::AVR850 => None,
+        }.or_else(|| Command::from_str(name).ok())?;
+        if self.supports_command(cc) { Some(cc) } else { None }
+    }
+}
+
+/// The RC5 commands used via the `SimulateRC5IRCommand` [Command](enum.Command.html).
+///
+/// The values of these variants are pairs of `u8` values. Python and D can handle enum variants
+/// being tuples, but it seems that Rust cannot. Thus, define the variants and the values
+/// separately. :-(
+///
+///The order of the variants is as it is written in the table of the documentation.  It is
+/// neither numeric order nor function order.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum RC5Command {
+    Standby,
+    One,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    AccessLipsyncDelayControl,
+    Zero,
+    CycleBetweenVFDInformationPanels,
+    Rewind,
+    FastForward,
+    SkipBack,
+    SkipForward,
+    Stop,
+    Play,
+    Pause,
+    Disc_Record_EnterTrimMenu,
+    MENU_EnterSystemMenu,
+    NavigateUp,
+    PopUp_DolbyVolumeOnOff,
+    NavigateLeft,
+    OK,
+    NavigateRight,
+    Audio_RoomEQOnOff,
+    NavigateDown,
+    RTN_AccessSubwooferTrimControl,
+    HOME,
+    Mute,
+    IncreaseVolume,
+    MODE_CycleBetweenDecodingModes,
+    DISP_ChangeVFDBrightness,
+    ActivateDIRECTMode,
+    DecreaseVolume,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Radio,
+    Aux,
+    Net,
+    USB,
+    AV,
+    Sat,
+    PVR,
+    Game,
+    BD,
+    CD,
+    STB,
+    VCR,
+    Display,
+    PowerOn,
+    PowerOff,
+    ChangeControlToNextZone,
+    CycleBetweenOutputResolutions,
+    AccessBassControl,
+    AccessSpeakerTrimControls,
+    AccessTrebleControl,
+    Random,
+    Repeat,
+    DirectModeOn,
+    DirectModeOff,
+    MultiChannel,
+    Stereo,
+    DolbySurround,
+    DTSNeo6Cinema,
+    DTSNeo6Music,
+    DTSNeuralX,
+    Reserved,
+    DTSVirtualX,
+    FiveSevenChannelStereo,
+    DolbyDEX,
+    MuteOn,
+    MuteOff,
+    FM,
+    DAB,
+    LipSyncPlus5ms,
+    LipSyncMinus5ms,
+    SubTrimPlusHalfDb,
+    SubTrimMinusHalfDb,
+    DisplayOff,
+    DisplayL1,
+    DisplayL2,
+    BalanceLeft,
+    BalanceRight,
+    BassPlus1,
+    BassMinus1,
+    TreblePlus1,
+    TrebleMinus1,
+    SetZone2ToFollowZone1,
+    Zone2PowerOn,
+    Zone2PowerOff,
+    Zone2VolumePlus,
+    Zone2VolumeMinus,
+    Zone2Mute,
+    Zone2MuteOn,
+    Zone2MuteOff,
+    Zone2CD,
+    Zone2BD,
+    Zone2STB,
+    Zone2AV,
+    Zone2Game,
+    Zone2Aux,
+    Zone2PVR,
+    Zone2FM,
+    Zone2DAB,
+    Zone2USB,
+    Zone2NET,
+    Zone2Sat,
+    Zone2VCR,
+    SelectHDMIOut1,
+    SelectHDMIOut2,
+    SelectHDMIOut1And2,
+}
+
+/// The RC5 data for each [RC5Command](enum.RC5Command.html) variant. The entries are in the
+/// same order as the variants so that the data for a variant can be found by indexing.
+///
+/// Some codes are used by two variants, so mapping a code back to a variant gives an
+/// [RC5Aliases](struct.RC5Aliases.html) rather than a single variant.
+const RC5_DATA: [(RC5Command, (u8, u8)); 116] = [
+    (RC5Command::Standby, (0x10, 0x0c)),
+    (RC5Command::One, (0x10, 0x01)),
+    (RC5Command::Two, (0x10, 0x02)),
+    (RC5Command::Three, (0x10, 0x03)),
+    (RC5Command::Four, (0x10, 0x04)),
+    (RC5Command::Five, (0x10, 0x05)),
+    (RC5Command::Six, (0x10, 0x06)),
+    (RC5Command::Seven, (0x10, 0x07)),
+    (RC5Command::Eight, (0x10, 0x08)),
+    (RC5Command::Nine, (0x10, 0x09)),
+    (RC5Command::AccessLipsyncDelayControl, (0x10, 0x32)),
+    (RC5Command::Zero, (0x10, 0x00)),
+    (RC5Command::CycleBetweenVFDInformationPanels, (0x10, 0x37)),  // Repeat use of value by Blue.
+    (RC5Command::Rewind, (0x10, 0x79)),
+    (RC5Command::FastForward, (0x10, 0x34)),
+    (RC5Command::SkipBack, (0x10, 0x21)),
+    (RC5Command::SkipForward, (0x10, 0x0b)),
+    (RC5Command::Stop, (0x10, 0x36)),
+    (RC5Command::Play, (0x10, 0x35)),
+    (RC5Command::Pause, (0x10, 0x30)),
+    (RC5Command::Disc_Record_EnterTrimMenu, (0x10, 0x5a)),
+    (RC5Command::MENU_EnterSystemMenu, (0x10, 0x52)),
+    (RC5Command::NavigateUp, (0x10, 0x56)),
+    (RC5Command::PopUp_DolbyVolumeOnOff, (0x10, 0x46)),
+    (RC5Command::NavigateLeft, (0x10, 0x51)),
+    (RC5Command::OK, (0x10, 0x57)),
+    (RC5Command::NavigateRight, (0x10, 0x50)),
+    (RC5Command::Audio_RoomEQOnOff, (0x10, 0x1e)),
+    (RC5Command::NavigateDown, (0x10, 0x55)),
+    (RC5Command::RTN_AccessSubwooferTrimControl, (0x10, 0x33)),
+    (RC5Command::HOME, (0x10, 0x2b)),  // Repeat use of value by Yellow.
+    (RC5Command::Mute, (0x10, 0x0d)),
+    (RC5Command::IncreaseVolume, (0x10, 0x10)),
+    (RC5Command::MODE_CycleBetweenDecodingModes, (0x10, 0x20)),
+    (RC5Command::DISP_ChangeVFDBrightness, (0x10, 0x3b)),
+    (RC5Command::ActivateDIRECTMode, (0x10, 0x0a)),
+    (RC5Command::DecreaseVolume, (0x10, 0x11)),
+    (RC5Command::Red, (0x10, 0x29)),
+    (RC5Command::Green, (0x10, 0x2a)),
+    (RC5Command::Yellow, (0x10, 0x2b)),  // Repeat use of value by HOME.
+    (RC5Command::Blue, (0x10, 0x37)),  // Repeat use of value by CycleBetweenVFDInformationPanels.
+    (RC5Command::Radio, (0x10, 0x5b)),
+    (RC5Command::Aux, (0x10, 0x63)),
+    (RC5Command::Net, (0x10, 0x5c)),
+    (RC5Command::USB, (0x10, 0x5d)),
+    (RC5Command::AV, (0x10, 0x5e)),
+    (RC5Command::Sat, (0x10, 0x1b)),
+    (RC5Command::PVR, (0x10, 0x60)),
+    (RC5Command::Game, (0x10, 0x61)),
+    (RC5Command::BD, (0x10, 0x62)),
+    (RC5Command::CD, (0x10, 0x76)),
+    (RC5Command::STB, (0x10, 0x64)),
+    (RC5Command::VCR, (0x10, 0x77)),
+    (RC5Command::Display, (0x10, 0x3a)),
+    (RC5Command::PowerOn, (0x10, 0x7b)),
+    (RC5Command::PowerOff, (0x10, 0x7c)),
+    (RC5Command::ChangeControlToNextZone, (0x10, 0x5f)),
+    (RC5Command::CycleBetweenOutputResolutions, (0x10, 0x2f)),
+    (RC5Command::AccessBassControl, (0x10, 0x27)),
+    (RC5Command::AccessSpeakerTrimControls, (0x10, 0x25)),
+    (RC5Command::AccessTrebleControl, (0x10, 0x0e)),
+    (RC5Command::Random, (0x10, 0x4c)),
+    (RC5Command::Repeat, (0x10, 0x31)),
+    (RC5Command::DirectModeOn, (0x10, 0x4e)),
+    (RC5Command::DirectModeOff, (0x10, 0x4f)),
+    (RC5Command::MultiChannel, (0x10, 0x6a)),
+    (RC5Command::Stereo, (0x10, 0x6b)),
+    (RC5Command::DolbySurround, (0x10, 0x6e)),
+    (RC5Command::DTSNeo6Cinema, (0x10, 0x6f)),
+    (RC5Command::DTSNeo6Music, (0x10, 0x70)),
+    (RC5Command::DTSNeuralX, (0x10, 0x71)),
+    (RC5Command::Reserved, (0x10, 0x72)),
+    (RC5Command::DTSVirtualX, (0x10, 0x73)),
+    (RC5Command::FiveSevenChannelStereo, (0x10, 0x45)),
+    (RC5Command::DolbyDEX, (0x10, 0x17)),
+    (RC5Command::MuteOn, (0x10, 0x1a)),
+    (RC5Command::MuteOff, (0x10, 0x78)),
+    (RC5Command::FM, (0x10, 0x1c)),
+    (RC5Command::DAB, (0x10, 0x48)),
+    (RC5Command::LipSyncPlus5ms, (0x10, 0x0f)),
+    (RC5Command::LipSyncMinus5ms, (0x10, 0x65)),
+    (RC5Command::SubTrimPlusHalfDb, (0x10, 0x69)),
+    (RC5Command::SubTrimMinusHalfDb, (0x10, 0x6c)),
+    (RC5Command::DisplayOff, (0x10, 0x1f)),
+    (RC5Command::DisplayL1, (0x10, 0x22)),
+    (RC5Command::DisplayL2, (0x10, 0x23)),
+    (RC5Command::BalanceLeft, (0x10, 0x26)),
+    (RC5Command::BalanceRight, (0x10, 0x28)),
+    (RC5Command::BassPlus1, (0x10, 0x2c)),
+    (RC5Command::BassMinus1, (0x10, 0x2d)),
+    (RC5Command::TreblePlus1, (0x10, 0x2e)),
+    (RC5Command::TrebleMinus1, (0x10, 0x66)),
+    (RC5Command::SetZone2ToFollowZone1, (0x10, 0x14)),
+    (RC5Command::Zone2PowerOn, (0x17, 0x7b)),
+    (RC5Command::Zone2PowerOff, (0x17, 0x7c)),
+    (RC5Command::Zone2VolumePlus, (0x17, 0x01)),
+    (RC5Command::Zone2VolumeMinus, (0x17, 0x02)),
+    (RC5Command::Zone2Mute, (0x17, 0x03)),
+    (RC5Command::Zone2MuteOn, (0x17, 0x04)),
+    (RC5Command::Zone2MuteOff, (0x17, 0x05)),
+    (RC5Command::Zone2CD, (0x17, 0x06)),
+    (RC5Command::Zone2BD, (0x17, 0x07)),
+    (RC5Command::Zone2STB, (0x17, 0x08)),
+    (RC5Command::Zone2AV, (0x17, 0x09)),
+    (RC5Command::Zone2Game, (0x17, 0x0b)),
+    (RC5Command::Zone2Aux, (0x17, 0x0d)),
+    (RC5Command::Zone2PVR, (0x17, 0x0f)),
+    (RC5Command::Zone2FM, (0x17, 0x0e)),
+    (RC5Command::Zone2DAB, (0x17, 0x10)),
+    (RC5Command::Zone2USB, (0x17, 0x12)),
+    (RC5Command::Zone2NET, (0x17, 0x13)),
+    (RC5Command::Zone2Sat, (0x17, 0x14)),
+    (RC5Command::Zone2VCR, (0x17, 0x15)),
+    (RC5Command::SelectHDMIOut1, (0x10, 0x49)),
+    (RC5Command::SelectHDMIOut2, (0x10, 0x4a)),
+    (RC5Command::SelectHDMIOut1And2, (0x10, 0x4b)),
+];
+
+/// The [RC5Command](enum.RC5Command.html)s that apply to zone 2, all the others apply to
+/// zone 1.
+static ZONE_2_RC5_COMMANDS: &[RC5Command] = &[
+    RC5Command::SetZone2ToFollowZone1, RC5Command::Zone2PowerOn, RC5Command::Zone2PowerOff, RC5Command::Zone2VolumePlus,
+    RC5Command::Zone2VolumeMinus, RC5Command::Zone2Mute, RC5Command::Zone2MuteOn, RC5Command::Zone2MuteOff,
+    RC5Command::Zone2CD, RC5Command::Zone2BD, RC5Command::Zone2STB, RC5Command::Zone2AV, RC5Command::Zone2Game,
+    RC5Command::Zone2Aux, RC5Command::Zone2PVR, RC5Command::Zone2FM, RC5Command::Zone2DAB, RC5Command::Zone2USB,
+    RC5Command::Zone2NET, RC5Command::Zone2Sat, RC5Command::Zone2VCR,
+];
+
+impl RC5Command {
+    /// The zone the RC5 command applies to.
+    pub fn zone(self: &Self) -> ZoneNumber {
+        if ZONE_2_RC5_COMMANDS.contains(self) { ZoneNumber::Two } else { ZoneNumber::One }
+    }
+}
+
+/// The [RC5Command](enum.RC5Command.html)s that share an RC5 code.
+///
+/// Almost all codes have one [RC5Command](enum.RC5Command.html), but HOME and Yellow share a
+/// code, as do CycleBetweenVFDInformationPanels and Blue.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RC5Aliases(Vec<RC5Command>);
+
+impl RC5Aliases {
+    /// All the [RC5Command](enum.RC5Command.html)s for the code, in table order.
+    pub fn commands(self: &Self) -> &[RC5Command] {
+        &self.0
+    }
+
+    /// The first [RC5Command](enum.RC5Command.html) for the code in the table.
+    pub fn primary(self: &Self) -> RC5Command {
+        self.0[0]
+    }
+
+    /// Is the given [RC5Command](enum.RC5Command.html) one of the commands for the code.
+    pub fn contains(self: &Self, rc5command: RC5Command) -> bool {
+        self.0.contains(&rc5command)
+    }
+}
+
+impl TryFrom<(u8, u8)> for RC5Aliases {
+    type Error = ProtocolError;
+    fn try_from(value: (u8, u8)) -> Result<Self, Self::Error> {
+        let commands: Vec<RC5Command> = RC5_DATA.iter()
+            .filter(|(_, data)| *data == value)
+            .map(|(rc5command, _)| *rc5command)
+            .collect();
+        if commands.is_empty() { Err(ProtocolError::UnknownRC5Code(value.0, value.1)) } else { Ok(RC5Aliases(commands)) }
+    }
+}
+
+impl TryFrom<&[u8]> for RC5Aliases {
+    type Error = ProtocolError;
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != 2 { return Err(ProtocolError::BadDataLength(Command::SimulateRC5IRCommand, data.len())); }
+        RC5Aliases::try_from((data[0], data[1]))
+    }
+}
+
+/// Accessor for the [RC5Command](enum.RC5Command.html) variant values.
+pub const fn get_rc5command_data(rc5command: RC5Command) -> (u8, u8) {
+    RC5_DATA[rc5command as usize].1
+}
+
+/// The answer codes (Ac entries) that can be received from the amplifier.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[repr(u8)]
+pub enum AnswerCode {
+    StatusUpdate = 0x00,
+    ZoneInvalid = 0x82,
+    CommandNotRecognized = 0x83,
+    ParameterNotRecognized = 0x84,
+    CommandInvalidAtThisTime = 0x85,
+    InvalidDataLength = 0x86,
+}
+
+impl AnswerCode {
+    /// The [ErrorAnswerCode](enum.ErrorAnswerCode.html) for this answer code, `None` for a
+    /// status update.
+    pub fn error(self: Self) -> Option<ErrorAnswerCode> {
+        match self {
+            Self::StatusUpdate => None,
+            Self::ZoneInvalid => Some(ErrorAnswerCode::ZoneInvalid),
+            Self::CommandNotRecognized => Some(ErrorAnswerCode::CommandNotRecognized),
+            Self::ParameterNotRecognized => Some(ErrorAnswerCode::ParameterNotRecognized),
+            Self::CommandInvalidAtThisTime => Some(ErrorAnswerCode::CommandInvalidAtThisTime),
+            Self::InvalidDataLength => Some(ErrorAnswerCode::InvalidDataLength),
+        }
+    }
+}
+
+/// The [AnswerCode](enum.AnswerCode.html)s that say the amplifier could not do what was asked,
+/// i.e. all but `StatusUpdate`.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[repr(u8)]
+pub enum ErrorAnswerCode {
+    ZoneInvalid = 0x82,
+    CommandNotRecognized = 0x83,
+    ParameterNotRecognized = 0x84,
+    CommandInvalidAtThisTime = 0x85,
+    InvalidDataLength = 0x86,
+}
+
+impl From<ErrorAnswerCode> for AnswerCode {
+    fn from(error: ErrorAnswerCode) -> Self {
+        match error {
+            ErrorAnswerCode::ZoneInvalid => Self::ZoneInvalid,
+            ErrorAnswerCode::CommandNotRecognized => Self::CommandNotRecognized,
+            ErrorAnswerCode::ParameterNotRecognized => Self::ParameterNotRecognized,
+            ErrorAnswerCode::CommandInvalidAtThisTime => Self::CommandInvalidAtThisTime,
+            ErrorAnswerCode::InvalidDataLength => Self::InvalidDataLength,
+        }
+    }
+}
+
+/// The three levels of brightness of the amplifier display.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[repr(u8)]
+pub enum Brightness {
+    Off = 0,
+    Level1 = 1,
+    Level2 = 2,
+}
+
+/// The various sources the amplifier can use.
+///
+/// Numeric representation as per the `RequestCurrentSource` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[repr(u8)]
+pub enum Source {
+    FollowZone1 = 0x00,
+    CD = 0x01,
+    BD = 0x02,
+    AV = 0x03,
+    SAT = 0x04,
+    PVR = 0x05,
+    VCR = 0x06,
+    AUX = 0x08,
+    DISPLAY = 0x09,
+    TUNER = 0x0B,  // TUNER (FM) according to the documentation.
+    TUNERDAB = 0x0C,  // (AVR450/750 only) according to the documentation.
+    NET = 0x0E,
+    USB = 0x0F,
+    STB = 0x10,
+    GAME = 0x11,
+}
+
+impl Source {
+    /// All the sources, in numeric representation order.
+    pub const ALL: [Source; 15] = [
+        Self::FollowZone1, Self::CD, Self::BD, Self::AV, Self::SAT, Self::PVR, Self::VCR, Self::AUX, Self::DISPLAY,
+        Self::TUNER, Self::TUNERDAB, Self::NET, Self::USB, Self::STB, Self::GAME,
+    ];
+
+    /// Can the source be selected for the zone: only zone 2 can follow zone 1, and only zone 1
+    /// can use the display.
+    pub fn applies_to_zone(self: &Self, zone: ZoneNumber) -> bool {
+        source_rc5command(zone, *self).is_ok()
+    }
+
+    /// The [VideoSource](enum.VideoSource.html) of this source, `None` if the source has no
+    /// HDMI video input.
+    pub fn video_source(self: &Self) -> Option<VideoSource> {
+        match self {
+            Self::BD => Some(VideoSource::BD),
+            Self::SAT => Some(VideoSource::SAT),
+            Self::AV => Some(VideoSource::AV),
+            Self::PVR => Some(VideoSource::PVR),
+            Self::VCR => Some(VideoSource::VCR),
+            Self::GAME => Some(VideoSource::Game),
+            Self::STB => Some(VideoSource::STB),
+            _ => None,
+        }
+    }
+}
+
+/// The video sources.
+///
+/// Numeric representation as per the `VideoSelection` [Command](enum.Command.html) return value.
+#[derive(Copy, Clone, Debug, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum VideoSource {
+    BD = 0x00,
+    SAT = 0x01,
+    AV = 0x02,
+    PVR = 0x03,
+    VCR = 0x04,
+    Game = 0x05,
+    STB = 0x06,
+}
+
+/// An analogue of bool to represent the power state of a zone.
+///
+/// Numeric representation as per `Power` [Command](enum.Command.html) return value.
+/// The UI needs a string representation and this avoids spelling errors.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[repr(u8)]
+pub enum PowerState {
+    Standby = 0x00,
+    On = 0x01,
+}
+
+impl From<bool> for PowerState {
+    fn from(b: bool) -> Self {
+        match b {
+            false => Self::Standby,
+            true => Self::On,
+        }
+    }
+}
+
+impl From<PowerState> for bool {
+    fn from(p: PowerState) -> Self {
+        match p {
+            PowerState::Standby => false,
+            PowerState::On => true,
+        }
+    }
+}
+
+/// An analogue of bool to represent the mute state of a zone.
+///
+/// Numeric representation as per the `RequestMuteState` [Command](enum.Command.html) return value.
+/// The UI needs a string representation and this avoids spelling errors.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[repr(u8)]
+pub enum MuteState {
+    Muted = 0x00,
+    NotMuted = 0x01,
+}
+
+impl From<bool> for MuteState {
+    fn from(b: bool) -> Self {
+        match b {
+            true => Self::Muted,
+            false => Self::NotMuted,
+        }
+    }
+}
+
+impl From<MuteState> for bool {
+    fn from(m: MuteState) -> Self {
+        match m {
+            MuteState::Muted => true,
+            MuteState::NotMuted => false,
+        }
+    }
+}
+
+/// The audio input type of a source.
+///
+/// Numeric representation as per the `SelectAnalogueDigital` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum AudioInputType {
+    Analogue = 0x00,
+    Digital = 0x01,
+    HDMI = 0x02,
+}
+
+/// The decode modes for two channel input.
+///
+/// Numeric representation as per the `RequestDecodeModeStatus2ch` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum DecodeMode2ch {
+    Stereo = 0x01,
+    DolbySurround = 0x04,
+    DTSNeo6Cinema = 0x07,
+    DTSNeo6Music = 0x08,
+    FiveSevenChannelStereo = 0x09,
+    DTSNeuralX = 0x0A,
+    Reserved = 0x0B,
+    DTSVirtualX = 0x0C,
+}
+
+/// The decode modes for multi-channel input.
+///
+/// Numeric representation as per the `RequestDecodeModeStatusMCH` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum DecodeModeMCH {
+    StereoDownmix = 0x01,
+    MultiChannel = 0x02,
+    DTSNeuralX = 0x03,
+    DolbySurround = 0x06,
+    DTSVirtualX = 0x0C,
+}
+
+/// The video output resolutions.
+///
+/// Numeric representation as per the `SetRequestVideoOutputResolution` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum VideoOutputResolution {
+    SDProgressive = 0x02,
+    HD720p = 0x03,
+    HD1080i = 0x04,
+    HD1080p = 0x05,
+    Preferred = 0x06,
+    Bypass = 0x07,
+    UHD4K = 0x08,
+}
+
+/// The menu currently being shown by the amplifier.
+///
+/// Numeric representation as per the `RequestMenuStatus` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum MenuStatus {
+    NoMenu = 0x00,
+    SetUp = 0x02,
+    Trim = 0x03,
+    Bass = 0x04,
+    Treble = 0x05,
+    Sync = 0x06,
+    Sub = 0x07,
+    Tuner = 0x08,
+    Network = 0x09,
+    USB = 0x0A,
+}
+
+/// The levels of dynamic range compression.
+///
+/// Numeric representation as per the `Compression` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum Compression {
+    Off = 0x00,
+    Medium = 0x01,
+    High = 0x02,
+}
+
+/// The room equalisation in use.
+///
+/// Numeric representation as per the `RoomEqualisation` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum RoomEqualisation {
+    Off = 0x00,
+    EQ1 = 0x01,
+    EQ2 = 0x02,
+    EQ3 = 0x03,
+}
+
+/// The IMAX Enhanced mode.
+///
+/// Numeric representation as per the `IMAXEnhanced` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum IMAXEnhancedMode {
+    Off = 0x00,
+    On = 0x01,
+    Auto = 0x02,
+}
+
+/// The state of playback of the network source.
+///
+/// Numeric representation as per the `NetworkPlaybackStatus` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum NetworkPlaybackStatus {
+    Stopped = 0x00,
+    Transitioning = 0x01,
+    Playing = 0x02,
+    Paused = 0x03,
+}
+
+/// The HDMI outputs in use.
+///
+/// Numeric representation as per the `SetRequestVideoOutputSwitching` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum VideoOutput {
+    HDMIOut1 = 0x02,
+    HDMIOut2 = 0x03,
+    HDMIOut1And2 = 0x04,
+}
+
+/// The type of the video input of a source, AVR600 only.
+///
+/// Numeric representation as per the `SetRequestVideoInputType` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum VideoInputType {
+    Composite = 0x00,
+    SVideo = 0x01,
+    Component = 0x02,
+    HDMI = 0x03,
+}
+
+/// The picture modes of the video processor, AVR600 only.
+///
+/// Numeric representation as per the `SetRequestPictureMode` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum PictureMode {
+    Cinema = 0x00,
+    Animation = 0x01,
+    Video = 0x02,
+}
+
+/// The strength of a video processing filter, AVR600 only.
+///
+/// Numeric representation as per the `SetRequestEdgeEnhancement`, `SetRequestMosquitoNR`,
+/// `SetRequestNoiseReduction`, and `SetRequestBlockNoiseReduction`
+/// [Command](enum.Command.html) return values.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum FilterLevel {
+    Off = 0x00,
+    Low = 0x01,
+    Medium = 0x02,
+    High = 0x03,
+}
+
+/// The frame rate of the video output, AVR600 only.
+///
+/// Numeric representation as per the `SetRequestOutputFrameRate` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum OutputFrameRate {
+    Auto = 0x00,
+    Hz50 = 0x01,
+    Hz60 = 0x02,
+}
+
+/// The band of a tuner preset.
+///
+/// Numeric representation as per the second byte of the `RequestPresetDetails`
+/// [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+enum PresetBand {
+    FMFrequency = 0x01,
+    FMName = 0x02,
+    DABName = 0x03,
+}
+
+/// The station stored in a tuner preset, as per the `RequestPresetDetails`
+/// [Command](enum.Command.html) return value: an FM station is stored as its frequency, or its
+/// RDS name, a DAB station as its name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PresetStation {
+    FMFrequency { megahertz: u8, ten_kilohertz: u8 },
+    FMName(String),
+    DABStation(String),
+}
+
+impl PresetStation {
+    /// The preset number and station represented by the data of a packet.
+    fn decode(data: &[u8]) -> Result<(u8, Self), ProtocolError> {
+        let cc = Command::RequestPresetDetails;
+        if data.len() < 3 { return Err(ProtocolError::BadDataLength(cc, data.len())); }
+        let band = PresetBand::from_u8(data[1]).ok_or(ProtocolError::BadValue(cc, data[1]))?;
+        let station = match band {
+            PresetBand::FMFrequency => {
+                if data.len() != 4 { return Err(ProtocolError::BadDataLength(cc, data.len())); }
+                Self::FMFrequency { megahertz: data[2], ten_kilohertz: data[3] }
+            },
+            PresetBand::FMName => Self::FMName(decode_broadcast_text(&data[2..])),
+            PresetBand::DABName => Self::DABStation(decode_broadcast_text(&data[2..])),
+        };
+        Ok((data[0], station))
+    }
+}
+
+impl fmt::Display for PresetStation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FMFrequency { megahertz, ten_kilohertz } => write!(f, "FM {}.{:02} MHz", megahertz, ten_kilohertz),
+            Self::FMName(name) => write!(f, "FM {}", name),
+            Self::DABStation(name) => write!(f, "DAB {}", name),
+        }
+    }
+}
+
+/// The format of the incoming audio stream.
+///
+/// Numeric representation as per the first byte of the `RequestIncomingAudioFormat`
+/// [Command](enum.Command.html) return value.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum AudioFormat {
+    PCM = 0x00,
+    AnalogueDirect = 0x01,
+    DolbyDigital = 0x02,
+    DolbyDigitalEX = 0x03,
+    DolbyDigitalSurround = 0x04,
+    DolbyDigitalPlus = 0x05,
+    DolbyTrueHD = 0x06,
+    DTS = 0x07,
+    DTS_96_24 = 0x08,
+    DTSESMatrix = 0x09,
+    DTSESDiscrete = 0x0A,
+    DTSESMatrix_96_24 = 0x0B,
+    DTSESDiscrete_96_24 = 0x0C,
+    DTSHDMasterAudio = 0x0D,
+    DTSHDHighResolutionAudio = 0x0E,
+    DTSLowBitRate = 0x0F,
+    DTSCore = 0x10,
+    PCMZero = 0x13,
+    Unsupported = 0x14,
+    Undetected = 0x15,
+    DolbyAtmos = 0x16,
+    DTSX = 0x17,
+    IMAXEnhanced = 0x18,
+    Auro3D = 0x19,
+}
+
+impl fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::PCM => "PCM",
+            Self::AnalogueDirect => "Analogue direct",
+            Self::DolbyDigital => "Dolby Digital",
+            Self::DolbyDigitalEX => "Dolby Digital EX",
+            Self::DolbyDigitalSurround => "Dolby Digital Surround",
+            Self::DolbyDigitalPlus => "Dolby Digital Plus",
+            Self::DolbyTrueHD => "Dolby TrueHD",
+            Self::DTS => "DTS",
+            Self::DTS_96_24 => "DTS 96/24",
+            Self::DTSESMatrix => "DTS-ES Matrix",
+            Self::DTSESDiscrete => "DTS-ES Discrete",
+            Self::DTSESMatrix_96_24 => "DTS-ES Matrix 96/24",
+            Self::DTSESDiscrete_96_24 => "DTS-ES Discrete 96/24",
+            Self::DTSHDMasterAudio => "DTS-HD Master Audio",
+            Self::DTSHDHighResolutionAudio => "DTS-HD High Resolution Audio",
+            Self::DTSLowBitRate => "DTS Low Bit Rate",
+            Self::DTSCore => "DTS Core",
+            Self::PCMZero => "PCM zero",
+            Self::Unsupported => "Unsupported",
+            Self::Undetected => "Undetected",
+            Self::DolbyAtmos => "Dolby Atmos",
+            Self::DTSX => "DTS:X",
+            Self::IMAXEnhanced => "IMAX Enhanced",
+            Self::Auro3D => "Auro-3D",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The speakers carried by the incoming audio stream, other than the LFE channel.
+///
+/// Numeric representation as per the second byte of the `RequestIncomingAudioFormat`
+/// [Command](enum.Command.html) return value for streams without an LFE channel.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum ChannelLayout {
+    DualMono = 0x00,
+    CentreOnly = 0x01,
+    StereoOnly = 0x02,
+    StereoMonoSurround = 0x03,
+    StereoSurroundLR = 0x04,
+    StereoSurroundLRMonoSurroundBack = 0x05,
+    StereoSurroundLRSurroundBackLR = 0x06,
+    StereoSurroundLRMatrixSurroundBack = 0x07,
+    StereoCentre = 0x08,
+    StereoCentreMonoSurround = 0x09,
+    StereoCentreSurroundLR = 0x0A,
+    StereoCentreSurroundLRMonoSurroundBack = 0x0B,
+    StereoCentreSurroundLRSurroundBackLR = 0x0C,
+    StereoCentreSurroundLRMatrixSurroundBack = 0x0D,
+    StereoDownmixLtRt = 0x0E,
+    StereoOnlyLoRo = 0x0F,
+}
+
+impl fmt::Display for ChannelLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::DualMono => "Dual mono",
+            Self::CentreOnly => "Centre",
+            Self::StereoOnly => "Stereo",
+            Self::StereoMonoSurround => "Stereo + mono surround",
+            Self::StereoSurroundLR => "Stereo + surround L/R",
+            Self::StereoSurroundLRMonoSurroundBack => "Stereo + surround L/R + mono surround back",
+            Self::StereoSurroundLRSurroundBackLR => "Stereo + surround L/R + surround back L/R",
+            Self::StereoSurroundLRMatrixSurroundBack => "Stereo + surround L/R with matrix surround back",
+            Self::StereoCentre => "Stereo + centre",
+            Self::StereoCentreMonoSurround => "Stereo + centre + mono surround",
+            Self::StereoCentreSurroundLR => "Stereo + centre + surround L/R",
+            Self::StereoCentreSurroundLRMonoSurroundBack => "Stereo + centre + surround L/R + mono surround back",
+            Self::StereoCentreSurroundLRSurroundBackLR => "Stereo + centre + surround L/R + surround back L/R",
+            Self::StereoCentreSurroundLRMatrixSurroundBack => "Stereo + centre + surround L/R with matrix surround back",
+            Self::StereoDownmixLtRt => "Stereo downmix Lt/Rt",
+            Self::StereoOnlyLoRo => "Stereo Lo/Ro",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The offset of the channel configuration values of streams with an LFE channel from those
+/// of the same [ChannelLayout](enum.ChannelLayout.html) without.
+const LFE_CHANNEL_OFFSET: u8 = 0x10;
+
+/// The channel configuration of the incoming audio stream, as per the second byte of the
+/// `RequestIncomingAudioFormat` [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChannelConfiguration {
+    Channels { layout: ChannelLayout, lfe: bool },
+    Unknown,
+    Undetected,
+}
+
+impl ChannelConfiguration {
+    /// The channel configuration represented by a byte in a packet, `None` if it is not a
+    /// valid channel configuration.
+    pub fn decode(byte: u8) -> Option<Self> {
+        match byte {
+            0x20 => Some(Self::Unknown),
+            0x21 => Some(Self::Undetected),
+            _ => {
+                let lfe = byte >= LFE_CHANNEL_OFFSET;
+                let layout = ChannelLayout::from_u8(if lfe { byte - LFE_CHANNEL_OFFSET } else { byte })?;
+                Some(Self::Channels { layout, lfe })
+            },
+        }
+    }
+
+    /// The byte representing this channel configuration in a packet.
+    pub fn encode(self: &Self) -> u8 {
+        match self {
+            Self::Channels { layout, lfe } => *layout as u8 + if *lfe { LFE_CHANNEL_OFFSET } else { 0 },
+            Self::Unknown => 0x20,
+            Self::Undetected => 0x21,
+        }
+    }
+}
+
+impl fmt::Display for ChannelConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Channels { layout, lfe: false } => write!(f, "{}", layout),
+            Self::Channels { layout, lfe: true } => write!(f, "{} + LFE", layout),
+            Self::Unknown => write!(f, "Unknown"),
+            Self::Undetected => write!(f, "Undetected"),
+        }
+    }
+}
+
+/// The sample rate of the incoming audio stream.
+///
+/// Numeric representation as per the `RequestIncomingAudioSampleRate`
+/// [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum SampleRate {
+    Hz32000 = 0x00,
+    Hz44100 = 0x01,
+    Hz48000 = 0x02,
+    Hz88200 = 0x03,
+    Hz96000 = 0x04,
+    Hz176400 = 0x05,
+    Hz192000 = 0x06,
+    Unknown = 0x07,
+    Undetected = 0x08,
+}
+
+impl SampleRate {
+    /// The sample rate in Hz, if known.
+    pub fn hertz(self: &Self) -> Option<u32> {
+        match self {
+            Self::Hz32000 => Some(32_000),
+            Self::Hz44100 => Some(44_100),
+            Self::Hz48000 => Some(48_000),
+            Self::Hz88200 => Some(88_200),
+            Self::Hz96000 => Some(96_000),
+            Self::Hz176400 => Some(176_400),
+            Self::Hz192000 => Some(192_000),
+            Self::Unknown | Self::Undetected => None,
+        }
+    }
+}
+
+impl fmt::Display for SampleRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.hertz() {
+            Some(hertz) => write!(f, "{} kHz", hertz as f32 / 1000.0),
+            None => write!(f, "{:?}", self),
+        }
+    }
+}
+
+/// The aspect ratio of the incoming video stream.
+///
+/// Numeric representation as per the seventh byte of the `RequestIncomingVideoParameters`
+/// [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum AspectRatio {
+    Undefined = 0x00,
+    Ratio4x3 = 0x01,
+    Ratio16x9 = 0x02,
+}
+
+impl fmt::Display for AspectRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Undefined => write!(f, "Undefined"),
+            Self::Ratio4x3 => write!(f, "4:3"),
+            Self::Ratio16x9 => write!(f, "16:9"),
+        }
+    }
+}
+
+/// The colourspace of the incoming video stream.
+///
+/// Numeric representation as per the eighth byte of the `RequestIncomingVideoParameters`
+/// [Command](enum.Command.html) return value.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum Colourspace {
+    Normal = 0x00,
+    HDR10 = 0x01,
+    DolbyVision = 0x02,
+    HLG = 0x03,
+    HDR10Plus = 0x04,
+}
+
+impl fmt::Display for Colourspace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Normal => write!(f, "SDR"),
+            Self::HDR10 => write!(f, "HDR10"),
+            Self::DolbyVision => write!(f, "Dolby Vision"),
+            Self::HLG => write!(f, "HLG"),
+            Self::HDR10Plus => write!(f, "HDR10+"),
+        }
+    }
+}
+
+/// The parameters of the incoming video stream, as per the `RequestIncomingVideoParameters`
+/// [Command](enum.Command.html) return value.
+///
+/// The amplifier reports a resolution of 0×0 when there is no video stream.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VideoParameters {
+    pub horizontal_resolution: u16,
+    pub vertical_resolution: u16,
+    pub refresh_rate: u8,
+    pub interlaced: bool,
+    pub aspect_ratio: AspectRatio,
+    pub colourspace: Colourspace,
+}
+
+impl VideoParameters {
+    /// The number of bytes of data in a packet carrying video parameters.
+    pub const DATA_LENGTH: usize = 8;
+
+    /// Whether there is a video stream to describe.
+    pub fn is_present(self: &Self) -> bool {
+        self.horizontal_resolution != 0 && self.vertical_resolution != 0
+    }
+
+    /// The resolution, scan and refresh rate in the usual notation, e.g. "1920×1080i 50 Hz".
+    pub fn resolution(self: &Self) -> String {
+        if !self.is_present() { return "No video".to_string(); }
+        format!(
+            "{}×{}{} {} Hz",
+            self.horizontal_resolution, self.vertical_resolution, if self.interlaced { "i" } else { "p" }, self.refresh_rate
+        )
+    }
+
+    /// The video parameters represented by the data of a packet.
+    pub fn decode(data: &[u8]) -> Result<Self, ProtocolError> {
+        let cc = Command::RequestIncomingVideoParameters;
+        if data.len() != Self::DATA_LENGTH { return Err(ProtocolError::BadDataLength(cc, data.len())); }
+        let interlaced = match data[5] {
+            0x00 => false,
+            0x01 => true,
+            x => return Err(ProtocolError::BadValue(cc, x)),
+        };
+        Ok(Self {
+            horizontal_resolution: u16::from_be_bytes([data[0], data[1]]),
+            vertical_resolution: u16::from_be_bytes([data[2], data[3]]),
+            refresh_rate: data[4],
+            interlaced,
+            aspect_ratio: AspectRatio::from_u8(data[6]).ok_or(ProtocolError::BadValue(cc, data[6]))?,
+            colourspace: Colourspace::from_u8(data[7]).ok_or(ProtocolError::BadValue(cc, data[7]))?,
+        })
+    }
+
+    /// The data of a packet representing these video parameters.
+    pub fn encode(self: &Self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(Self::DATA_LENGTH);
+        data.extend_from_slice(&self.horizontal_resolution.to_be_bytes());
+        data.extend_from_slice(&self.vertical_resolution.to_be_bytes());
+        data.push(self.refresh_rate);
+        data.push(self.interlaced as u8);
+        data.push(self.aspect_ratio as u8);
+        data.push(self.colourspace as u8);
+        data
+    }
+}
+
+impl fmt::Display for VideoParameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.resolution())?;
+        if !self.is_present() { return Ok(()); }
+        if self.aspect_ratio != AspectRatio::Undefined { write!(f, ", {}", self.aspect_ratio)?; }
+        write!(f, ", {}", self.colourspace)
+    }
+}
+
+/// The first byte value of a [SoftwareComponent](enum.SoftwareComponent.html).
+const SOFTWARE_COMPONENT_BASE: u8 = 0xF0;
+
+/// The software components of the amplifier that have a version.
+///
+/// Representation as per the `SoftwareVersion` [Command](enum.Command.html) request value and
+/// the first byte of the return value: 0xF0 to 0xF3 for the known components, the other bytes
+/// from 0xF0 up being components that newer amplifier software may report.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SoftwareComponent {
+    RS232Protocol,
+    Host,
+    OSD,
+    DSP,
+    Other(u8),
+}
+
+impl SoftwareComponent {
+    /// All the known components, in request value order. These are the components queried when
+    /// connecting to an amplifier.
+    pub const ALL: [SoftwareComponent; 4] = [Self::RS232Protocol, Self::Host, Self::OSD, Self::DSP];
+
+    /// The byte representing this component in a packet.
+    pub fn encode(self: &Self) -> u8 {
+        match self {
+            Self::RS232Protocol => 0xF0,
+            Self::Host => 0xF1,
+            Self::OSD => 0xF2,
+            Self::DSP => 0xF3,
+            Self::Other(byte) => *byte,
+        }
+    }
+
+    /// The component represented by a byte in a packet, `None` if it is not a component.
+    pub fn decode(byte: u8) -> Option<Self> {
+        match byte {
+            0xF0 => Some(Self::RS232Protocol),
+            0xF1 => Some(Self::Host),
+            0xF2 => Some(Self::OSD),
+            0xF3 => Some(Self::DSP),
+            b if b >= SOFTWARE_COMPONENT_BASE => Some(Self::Other(b)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SoftwareComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RS232Protocol => write!(f, "RS232 protocol"),
+            Self::Host => write!(f, "Host"),
+            Self::OSD => write!(f, "OSD"),
+            Self::DSP => write!(f, "DSP"),
+            Self::Other(byte) => write!(f, "Component 0x{:02X}", byte),
+        }
+    }
+}
+
+/// The version of a [SoftwareComponent](enum.SoftwareComponent.html) of the amplifier.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SoftwareVersion {
+    pub component: SoftwareComponent,
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl fmt::Display for SoftwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}.{}", self.component, self.major, self.minor)
+    }
+}
+
+/// The largest number of steps, either side of zero, of a signed offset value.
+const MAX_OFFSET_STEPS: i8 = 12;
+
+/// The bit marking a negative value in the sign and magnitude encoding of signed offsets.
+const OFFSET_NEGATIVE: u8 = 0x80;
+
+/// Check a number of steps is in the range of a signed offset value.
+fn check_offset_steps(steps: i8) -> Result<i8, ProtocolError> {
+    check_signed_steps(steps, MAX_OFFSET_STEPS)
+}
+
+/// Check a number of steps is in the range [-max, max].
+fn check_signed_steps(steps: i8, max: i8) -> Result<i8, ProtocolError> {
+    if (-max..=max).contains(&steps) { Ok(steps) }
+    else { Err(ProtocolError::ValueOutOfRange(steps as i16, -max as i16, max as i16)) }
+}
+
+/// Encode a number of steps: 0x00 to 0x0C for positive values, 0x81 to 0x8C for negative values.
+fn encode_offset_steps(steps: i8) -> u8 {
+    if steps < 0 { OFFSET_NEGATIVE | steps.unsigned_abs() } else { steps as u8 }
+}
+
+/// Decode a number of steps, `None` if the byte is not a valid encoding.
+fn decode_offset_steps(byte: u8) -> Option<i8> {
+    decode_signed_steps(byte, MAX_OFFSET_STEPS)
+}
+
+/// Decode a number of steps in the range [-max, max] using the same encoding as signed
+/// offsets, `None` if the byte is not a valid encoding.
+fn decode_signed_steps(byte: u8, max: i8) -> Option<i8> {
+    let magnitude = (byte & !OFFSET_NEGATIVE) as i8;
+    let steps = match byte & OFFSET_NEGATIVE {
+        0 => magnitude,
+        _ if magnitude != 0 => -magnitude,
+        _ => return None,
+    };
+    check_signed_steps(steps, max).ok()
+}
+
+/// A signed offset in whole dB, in the range [-12, 12], as used by the `TrebleEqualisation`,
+/// `BassEqualisation` and `Balance` [Command](enum.Command.html)s.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DecibelOffset(i8);
+
+impl DecibelOffset {
+    /// Create an offset of the given dB.
+    pub fn new(decibels: i8) -> Result<Self, ProtocolError> {
+        Ok(Self(check_offset_steps(decibels)?))
+    }
+
+    /// The offset in dB.
+    pub fn decibels(self: &Self) -> i8 { self.0 }
+
+    /// The byte representing this offset in a packet.
+    pub fn encode(self: &Self) -> u8 { encode_offset_steps(self.0) }
+
+    /// The offset represented by a byte in a packet, `None` if it is not a valid offset.
+    pub fn decode(byte: u8) -> Option<Self> { decode_offset_steps(byte).map(Self) }
+}
+
+impl fmt::Display for DecibelOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 { write!(f, "0 dB") } else { write!(f, "{:+} dB", self.0) }
+    }
+}
+
+/// A signed offset in half dB steps, in the range [-6.0, 6.0] dB, as used by the
+/// `SubwooferTrim` and `SetRequestSubStereoTrim` [Command](enum.Command.html)s.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HalfDecibelOffset(i8);
+
+impl HalfDecibelOffset {
+    /// Create an offset of the given number of half dB steps.
+    pub fn new(half_decibels: i8) -> Result<Self, ProtocolError> {
+        Ok(Self(check_offset_steps(half_decibels)?))
+    }
+
+    /// The offset in half dB steps.
+    pub fn half_decibels(self: &Self) -> i8 { self.0 }
+
+    /// The offset in dB.
+    pub fn decibels(self: &Self) -> f32 { self.0 as f32 / 2.0 }
+
+    /// The byte representing this offset in a packet.
+    pub fn encode(self: &Self) -> u8 { encode_offset_steps(self.0) }
+
+    /// The offset represented by a byte in a packet, `None` if it is not a valid offset.
+    pub fn decode(byte: u8) -> Option<Self> { decode_offset_steps(byte).map(Self) }
+}
+
+impl fmt::Display for HalfDecibelOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 { write!(f, "0.0 dB") } else { write!(f, "{:+.1} dB", self.decibels()) }
+    }
+}
+
+/// The largest lipsync delay, in steps of [LIPSYNC_STEP_MILLISECONDS](constant.LIPSYNC_STEP_MILLISECONDS.html).
+const MAX_LIPSYNC_STEPS: u8 = 0x32;
+
+/// The size of a lipsync delay step in ms.
+pub const LIPSYNC_STEP_MILLISECONDS: u16 = 5;
+
+/// The audio delay for lipsync, as used by the `LipsyncDelay` [Command](enum.Command.html):
+/// 0 ms to 250 ms in 5 ms steps.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LipsyncDelay(u8);
+
+impl LipsyncDelay {
+    /// Create a delay of the given number of 5 ms steps.
+    pub fn new(steps: u8) -> Result<Self, ProtocolError> {
+        if steps <= MAX_LIPSYNC_STEPS { Ok(Self(steps)) }
+        else { Err(ProtocolError::ValueOutOfRange(steps as i16, 0, MAX_LIPSYNC_STEPS as i16)) }
+    }
+
+    /// Create a delay of the given ms, which must be a whole number of 5 ms steps.
+    pub fn from_milliseconds(milliseconds: u16) -> Result<Self, ProtocolError> {
+        let max = MAX_LIPSYNC_STEPS as u16 * LIPSYNC_STEP_MILLISECONDS;
+        if milliseconds > max || !milliseconds.is_multiple_of(LIPSYNC_STEP_MILLISECONDS) {
+            let clamped = milliseconds.min(i16::MAX as u16) as i16;
+            return Err(ProtocolError::ValueOutOfRange(clamped, 0, max as i16));
+        }
+        Self::new((milliseconds / LIPSYNC_STEP_MILLISECONDS) as u8)
+    }
+
+    /// The number of 5 ms steps of delay.
+    pub fn steps(self: &Self) -> u8 { self.0 }
+
+    /// The delay in ms.
+    pub fn milliseconds(self: &Self) -> u16 { self.0 as u16 * LIPSYNC_STEP_MILLISECONDS }
+
+    /// The byte representing this delay in a packet.
+    pub fn encode(self: &Self) -> u8 { self.0 }
+
+    /// The delay represented by a byte in a packet, `None` if it is not a valid delay.
+    pub fn decode(byte: u8) -> Option<Self> { Self::new(byte).ok() }
+}
+
+impl fmt::Display for LipsyncDelay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ms", self.milliseconds())
+    }
+}
+
+/// The largest Dolby Pro Logic II dimension either side of zero.
+const MAX_DIMENSION_STEPS: i8 = 3;
+
+/// The Dolby Pro Logic II dimension, as used by the `DolbyProLogicIIDimension`
+/// [Command](enum.Command.html): a shift of the sound field in the range [-3, 3], using the
+/// same encoding as signed offsets.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Dimension(i8);
+
+impl Dimension {
+    /// Create a dimension of the given steps.
+    pub fn new(steps: i8) -> Result<Self, ProtocolError> {
+        Ok(Self(check_signed_steps(steps, MAX_DIMENSION_STEPS)?))
+    }
+
+    /// The dimension in steps.
+    pub fn steps(self: &Self) -> i8 { self.0 }
+
+    /// The byte representing this dimension in a packet.
+    pub fn encode(self: &Self) -> u8 { encode_offset_steps(self.0) }
+
+    /// The dimension represented by a byte in a packet, `None` if it is not a valid dimension.
+    pub fn decode(byte: u8) -> Option<Self> { decode_signed_steps(byte, MAX_DIMENSION_STEPS).map(Self) }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 { write!(f, "0") } else { write!(f, "{:+}", self.0) }
+    }
+}
+
+/// The largest Dolby Pro Logic II centre width.
+const MAX_CENTRE_WIDTH: u8 = 7;
+
+/// The Dolby Pro Logic II centre width, as used by the `DolbyProLogicIICentreWidth`
+/// [Command](enum.Command.html): in the range [0, 7].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CentreWidth(u8);
+
+impl CentreWidth {
+    /// Create a centre width of the given value.
+    pub fn new(width: u8) -> Result<Self, ProtocolError> {
+        if width <= MAX_CENTRE_WIDTH { Ok(Self(width)) }
+        else { Err(ProtocolError::ValueOutOfRange(width as i16, 0, MAX_CENTRE_WIDTH as i16)) }
+    }
+
+    /// The centre width.
+    pub fn width(self: &Self) -> u8 { self.0 }
+
+    /// The byte representing this centre width in a packet.
+    pub fn encode(self: &Self) -> u8 { self.0 }
+
+    /// The centre width represented by a byte in a packet, `None` if it is not a valid width.
+    pub fn decode(byte: u8) -> Option<Self> { Self::new(byte).ok() }
+}
+
+impl fmt::Display for CentreWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The largest Dolby Volume calibration offset, in dB, either side of zero.
+const MAX_CALIBRATION_OFFSET: i8 = 15;
+
+/// The Dolby Volume calibration offset, as used by the `DolbyVolumeCalibrationOffset`
+/// [Command](enum.Command.html): whole dB in the range [-15, 15], using the same encoding as
+/// signed offsets.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CalibrationOffset(i8);
+
+impl CalibrationOffset {
+    /// Create an offset of the given dB.
+    pub fn new(decibels: i8) -> Result<Self, ProtocolError> {
+        Ok(Self(check_signed_steps(decibels, MAX_CALIBRATION_OFFSET)?))
+    }
+
+    /// The offset in dB.
+    pub fn decibels(self: &Self) -> i8 { self.0 }
+
+    /// The byte representing this offset in a packet.
+    pub fn encode(self: &Self) -> u8 { encode_offset_steps(self.0) }
+
+    /// The offset represented by a byte in a packet, `None` if it is not a valid offset.
+    pub fn decode(byte: u8) -> Option<Self> { decode_signed_steps(byte, MAX_CALIBRATION_OFFSET).map(Self) }
+}
+
+impl fmt::Display for CalibrationOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 { write!(f, "0 dB") } else { write!(f, "{:+} dB", self.0) }
+    }
+}
+
+/// The largest picture adjustment either side of zero.
+const MAX_PICTURE_ADJUSTMENT: i8 = 50;
+
+/// An adjustment of the picture of the video processor, AVR600 only, as used by the
+/// `SetRequestBrightness`, `SetRequestContrast`, and `SetRequestColour`
+/// [Command](enum.Command.html)s: in the range [-50, 50], using the same encoding as signed
+/// offsets.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PictureAdjustment(i8);
+
+impl PictureAdjustment {
+    /// Create an adjustment of the given steps.
+    pub fn new(steps: i8) -> Result<Self, ProtocolError> {
+        Ok(Self(check_signed_steps(steps, MAX_PICTURE_ADJUSTMENT)?))
+    }
+
+    /// The adjustment in steps.
+    pub fn steps(self: &Self) -> i8 { self.0 }
+
+    /// The byte representing this adjustment in a packet.
+    pub fn encode(self: &Self) -> u8 { encode_offset_steps(self.0) }
+
+    /// The adjustment represented by a byte in a packet, `None` if it is not a valid adjustment.
+    pub fn decode(byte: u8) -> Option<Self> { decode_signed_steps(byte, MAX_PICTURE_ADJUSTMENT).map(Self) }
+}
+
+impl fmt::Display for PictureAdjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 { write!(f, "0") } else { write!(f, "{:+}", self.0) }
+    }
+}
+
+/// The largest volume level.
+const MAX_VOLUME_LEVEL: u8 = 99;
+
+/// The volume level that is approximately 0 dB on the AVR850 scale.
+const VOLUME_ZERO_DECIBEL_LEVEL: u8 = 83;
+
+/// The volume of a zone, as used by the `SetRequestVolume` [Command](enum.Command.html): a
+/// level in the range [0, 99].
+///
+/// On the AVR850 scale each level step is approximately 1 dB, with 83 being 0 dB.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Volume(u8);
+
+impl Volume {
+    /// The quietest volume.
+    pub const MIN: Volume = Volume(0);
+    /// The loudest volume.
+    pub const MAX: Volume = Volume(MAX_VOLUME_LEVEL);
+
+    /// Create a volume of the given level.
+    pub fn new(level: u8) -> Result<Self, ProtocolError> {
+        if level <= MAX_VOLUME_LEVEL { Ok(Self(level)) }
+        else { Err(ProtocolError::ValueOutOfRange(level as i16, 0, MAX_VOLUME_LEVEL as i16)) }
+    }
+
+    /// The volume level.
+    pub fn level(self: &Self) -> u8 { self.0 }
+
+    /// The approximate volume in dB on the AVR850 scale.
+    pub fn approximate_decibels(self: &Self) -> i8 { self.0 as i8 - VOLUME_ZERO_DECIBEL_LEVEL as i8 }
+
+    /// The volume the given number of steps louder, stopping at [MAX](#associatedconstant.MAX).
+    pub fn saturating_up(self: &Self, steps: u8) -> Self { Self(self.0.saturating_add(steps).min(MAX_VOLUME_LEVEL)) }
+
+    /// The volume the given number of steps quieter, stopping at [MIN](#associatedconstant.MIN).
+    pub fn saturating_down(self: &Self, steps: u8) -> Self { Self(self.0.saturating_sub(steps)) }
+
+    /// The byte representing this volume in a packet.
+    pub fn encode(self: &Self) -> u8 { self.0 }
+
+    /// The volume represented by a byte in a packet, `None` if it is not a valid volume.
+    pub fn decode(byte: u8) -> Option<Self> { Self::new(byte).ok() }
+}
+
+impl fmt::Display for Volume {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The value used as the start of packet value.
+pub static PACKET_START: u8 = 0x21;
+
+/// The value used as the end of packet value.
+pub static PACKET_END: u8 = 0x0d;
+
+/// The values used to represent the question of which value is currently set.
+pub static REQUEST_QUERY: u8 = 0xf0;
+
+/// The errors that can arise creating or parsing [Request](struct.Request.html)s and
+/// [Response](struct.Response.html)s.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProtocolError {
+    /// There are not (yet) enough bytes to form a packet.
+    Incomplete,
+    /// The first byte is not [PACKET_START](static.PACKET_START.html).
+    BadStartMarker,
+    /// The byte after the data is not [PACKET_END](static.PACKET_END.html).
+    BadEndMarker,
+    /// The zone byte is not a known [ZoneNumber](enum.ZoneNumber.html).
+    UnknownZone(u8),
+    /// The command byte is not a known [Command](enum.Command.html).
+    UnknownCommand(u8),
+    /// The answer code byte is not a known [AnswerCode](enum.AnswerCode.html).
+    UnknownAnswerCode(u8),
+    /// The data is more than the 255 bytes a packet can carry.
+    DataTooLong,
+    /// The response data is not the right length for the command.
+    BadDataLength(Command, usize),
+    /// The response data contains a value that is not valid for the command.
+    BadValue(Command, u8),
+    /// The response is an error answer rather than a status update.
+    ErrorAnswer(Command, AnswerCode),
+    /// The RC5 code is not that of any [RC5Command](enum.RC5Command.html).
+    UnknownRC5Code(u8, u8),
+    /// The AMX identification message does not have the expected structure.
+    BadAmxMessage,
+    /// The amplifier model does not understand the command.
+    UnsupportedCommand(Command),
+    /// The amplifier model does not have the source.
+    UnsupportedSource(Source),
+    /// The command does not apply to the zone.
+    CommandNotForZone(Command, ZoneNumber),
+    /// The [RC5Command](enum.RC5Command.html) does not apply to the zone.
+    RC5CommandNotForZone(RC5Command, ZoneNumber),
+    /// The source cannot be selected for the zone.
+    SourceNotForZone(Source, ZoneNumber),
+    /// The value is not in the range, given as minimum and maximum, of the value type.
+    ValueOutOfRange(i16, i16, i16),
+    /// The text is not a packet in the text notation.
+    BadNotation,
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Incomplete => write!(f, "Insufficient bytes to form a packet."),
+            Self::BadStartMarker => write!(f, "First byte is not the start of packet marker."),
+            Self::BadEndMarker => write!(f, "Final byte is not the end of packet marker."),
+            Self::UnknownZone(z) => write!(f, "Unknown zone number {:#04x}.", z),
+            Self::UnknownCommand(c) => write!(f, "Unknown command code {:#04x}.", c),
+            Self::UnknownAnswerCode(a) => write!(f, "Unknown answer code {:#04x}.", a),
+            Self::DataTooLong => write!(f, "Cannot have more than 255 bytes as data."),
+            Self::BadDataLength(cc, l) => write!(f, "Data length {} is not valid for {:?}.", l, cc),
+            Self::BadValue(cc, v) => write!(f, "Value {:#04x} is not valid for {:?}.", v, cc),
+            Self::ErrorAnswer(cc, ac) => write!(f, "Got answer {:?} for {:?}.", ac, cc),
+            Self::UnknownRC5Code(s, c) => write!(f, "Unknown RC5 code ({:#04x}, {:#04x}).", s, c),
+            Self::BadAmxMessage => write!(f, "Malformed AMX identification message."),
+            Self::UnsupportedCommand(cc) => write!(f, "Amplifier does not support {:?}.", cc),
+            Self::UnsupportedSource(s) => write!(f, "Amplifier does not have source {}.", s),
+            Self::CommandNotForZone(cc, z) => write!(f, "{:?} does not apply to zone {}.", cc, *z as u8),
+            Self::RC5CommandNotForZone(c, z) => write!(f, "RC5 command {:?} does not apply to zone {}.", c, *z as u8),
+            Self::SourceNotForZone(s, z) => write!(f, "Source {} cannot be selected for zone {}.", s, *z as u8),
+            Self::BadNotation => write!(f, "Malformed packet notation."),
+            Self::ValueOutOfRange(v, min, max) => write!(f, "Value {} is not in the range [{}, {}].", v, min, max),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProtocolError {}
+
+/// A request to the amplifier.
+///
+/// Deserialising a request goes through [new](#method.new) so the data length is checked.
+#[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize), serde(try_from = "RequestFields"))]
+pub struct Request {
+    pub zone: ZoneNumber,
+    pub cc: Command,
+    pub data: Vec<u8>,
+}
+
+/// The fields of a deserialised [Request](struct.Request.html) before they are checked.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RequestFields {
+    zone: ZoneNumber,
+    cc: Command,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RequestFields> for Request {
+    type Error = ProtocolError;
+
+    fn try_from(fields: RequestFields) -> Result<Self, Self::Error> {
+        Self::new(fields.zone, fields.cc, fields.data)
+    }
+}
+
+impl Request {
+    /// Create a new request.
+    ///
+    /// The data value is restricted to being at most 255 bytes long.
+    pub fn new(zone: ZoneNumber, cc: Command, data: Vec<u8>) -> Result<Self, ProtocolError> {
+        if data.len() > 255 { Err(ProtocolError::DataTooLong) }
+        else { Ok(Self {zone, cc, data}) }
+    }
+
+    /// Check that the command, and for `SimulateRC5IRCommand` the
+    /// [RC5Command](enum.RC5Command.html), of this request applies to its zone.
+    pub fn check_zone(self: &Self) -> Result<(), ProtocolError> {
+        if !self.cc.applies_to_zone(self.zone) { return Err(ProtocolError::CommandNotForZone(self.cc, self.zone)); }
+        if self.cc == Command::SimulateRC5IRCommand {
+            let rc5_command = RC5Aliases::try_from(&self.data[..])?.primary();
+            if rc5_command.zone() != self.zone { return Err(ProtocolError::RC5CommandNotForZone(rc5_command, self.zone)); }
+        }
+        Ok(())
+    }
+
+    /// Whether this request asks for a value from the amplifier rather than asking it to
+    /// change something.
+    pub fn is_query(self: &Self) -> bool {
+        self.cc == Command::SoftwareVersion || self.data == [REQUEST_QUERY]
+    }
+
+    /// Return the byte sequence representing this request.
+    ///
+    /// All requests are structured:
+    ///
+    /// - St (Start transmission): PACKET_START
+    /// - Zn (Zone number): 0x1, 0x2 for the zone number
+    /// - Cc (Command code): the code for the command
+    /// - Dl (Data Length): the number of data items following this item, excluding the Et
+    /// - Data: the parameters for the response of length n. n is limited to 255
+    /// - Et (End transmission): PACKET_END
+    pub fn to_bytes(self: &Self) -> Vec<u8> {
+        let dl = self.data.len();
+        if dl >= 256 { panic!("args array length not right."); }
+        let mut result = vec![PACKET_START, self.zone as u8, self.cc.code(), dl as u8];
+        result.extend(self.data.iter());
+        result.push(PACKET_END);
+        result
+    }
+
+    /// Parse the bytes in the buffer to create a tuple representing a request and the
+    /// number of bytes used for the request packet.
+    ///
+    /// All requests are structured:
+    ///
+    /// - St (Start transmission): PACKET_START
+    /// - Zn (Zone number): 0x1, 0x2 for the zone number
+    /// - Cc (Command code): the code for the command
+    /// - Dl (Data Length): the number of data items following this item, excluding the Et
+    /// - Data: the parameters for the response of length n. n is limited to 255
+    /// - Et (End transmission): PACKET_END
+    pub fn parse_bytes(buffer: &[u8]) -> Result<(Self, usize), ProtocolError> {
+        Self::parse_bytes_in_dialect(buffer, Dialect::default())
+    }
+
+    /// As [parse_bytes](#method.parse_bytes) but for an amplifier using the given
+    /// [Dialect](enum.Dialect.html) of the protocol.
+    pub fn parse_bytes_in_dialect(buffer: &[u8], dialect: Dialect) -> Result<(Self, usize), ProtocolError> {
+        let packet_length = buffer.len();
+        if packet_length == 0 { return Err(ProtocolError::Incomplete); }
+        if buffer[0] != PACKET_START { return Err(ProtocolError::BadStartMarker); }
+        if packet_length < 5 { return Err(ProtocolError::Incomplete); }
+        let zone = FromPrimitive::from_u8(buffer[1]).ok_or(ProtocolError::UnknownZone(buffer[1]))?;
+        let cc = dialect.command(buffer[2])?;
+        let dl = buffer[3] as usize;
+        let end_index = 4 + dl;
+        if end_index >= packet_length { return Err(ProtocolError::Incomplete); }
+        if buffer[end_index] != PACKET_END { return Err(ProtocolError::BadEndMarker); }
+        let data = buffer[4..end_index].to_vec();
+        Ok((Self{zone, cc, data}, end_index + 1))
+    }
+}
+
+impl fmt::Debug for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ds = f.debug_struct("Request");
+        ds.field("zone", &self.zone);
+        ds.field("cc", &self.cc);
+        ds.field("data", &self.data);
+        if self.cc == Command::SimulateRC5IRCommand {
+            if let Ok(aliases) = RC5Aliases::try_from(&self.data[..]) {
+                ds.field("rc5command", &aliases);
+            }
+        } else if self.data.len() == 1 && self.data[0] == REQUEST_QUERY {
+            ds.field("value", &"RequestQuery");
+        } else if self.cc == Command::RequestCurrentSource && self.data.len() == 1 {
+            if let Some(value) = Source::from_u8(self.data[0]) {
+                ds.field("value", &value);
+            }
+        }
+        ds.finish()
+    }
+}
+
+/// A response from the amplifier.
+///
+/// Deserialising a response goes through [new](#method.new) so the data length is checked.
+#[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize), serde(try_from = "ResponseFields"))]
+pub struct Response {
+    pub zone: ZoneNumber,
+    pub cc: Command,
+    pub ac: AnswerCode,
+    pub data: Vec<u8>,
+}
+
+/// The fields of a deserialised [Response](struct.Response.html) before they are checked.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct ResponseFields {
+    zone: ZoneNumber,
+    cc: Command,
+    ac: AnswerCode,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ResponseFields> for Response {
+    type Error = ProtocolError;
+
+    fn try_from(fields: ResponseFields) -> Result<Self, Self::Error> {
+        Self::new(fields.zone, fields.cc, fields.ac, fields.data)
+    }
+}
+
+impl Response {
+    /// Create a new response.
+    ///
+    /// The data value is restricted to being at most 255 bytes long.
+    pub fn new(zone: ZoneNumber, cc: Command, ac: AnswerCode, data: Vec<u8>) -> Result<Self, ProtocolError> {
+        if data.len() > 255 { Err(ProtocolError::DataTooLong) }
+        else { Ok(Self{zone, cc, ac, data}) }
+    }
+
+    /// Return the byte sequence representing this response.
+    ///
+    /// All responses are structured:
+    ///
+    /// - St (Start transmission): PACKET_START
+    /// - Zn (Zone number): 0x1, 0x2 for the zone number
+    /// - Cc (Command code): the code for the command
+    /// - Ac (Answer code): the answer code for the request
+    /// - Dl (Data Length): the number of data items following this item, excluding the Et
+    /// - Data: the parameters for the response of length n. n is limited to 255
+    /// - Et (End transmission): PACKET_END
+    pub fn to_bytes(self: &Self) -> Vec<u8> {
+        let dl = self.data.len();
+        if dl >= 256 { panic!("data length not right."); }
+        let mut result = vec![PACKET_START, self.zone as u8, self.cc.code(), self.ac as u8, dl as u8];
+        result.extend(self.data.iter());
+        result.push(PACKET_END);
+        result
+    }
+
+    /// Parse the bytes in the buffer to create a tuple representing a response and the
+    /// number of bytes used for the request packet.
+    ///
+    /// All responses are structured;
+    ///
+    /// - St (Start transmission): PACKET_START ‘!’
+    /// - Zn (Zone number): 0x1, 0x2 for the zone number
+    /// - Cc (Command code): the code for the command
+    /// - Ac (Answer code): the answer code for the request
+    /// - Dl (Data Length): the number of data items following this item, excluding the ETR
+    /// - Data: the parameters for the response of length n. n is limited to 255
+    /// - Et (End transmission): PACKET_END
+    pub fn parse_bytes(buffer: &[u8]) -> Result<(Self, usize), ProtocolError> {
+        Self::parse_bytes_in_dialect(buffer, Dialect::default())
+    }
+
+    /// As [parse_bytes](#method.parse_bytes) but for an amplifier using the given
+    /// [Dialect](enum.Dialect.html) of the protocol.
+    pub fn parse_bytes_in_dialect(buffer: &[u8], dialect: Dialect) -> Result<(Self, usize), ProtocolError> {
+        let packet_length = buffer.len();
+        if packet_length == 0 { return Err(ProtocolError::Incomplete); }
+        if buffer[0] != PACKET_START { return Err(ProtocolError::BadStartMarker); }
+        if packet_length < 6 { return Err(ProtocolError::Incomplete); }
+        let zone = FromPrimitive::from_u8(buffer[1]).ok_or(ProtocolError::UnknownZone(buffer[1]))?;
+        let cc = dialect.command(buffer[2])?;
+        let ac = FromPrimitive::from_u8(buffer[3]).ok_or(ProtocolError::UnknownAnswerCode(buffer[3]))?;
+        let dl = buffer[4] as usize;
+        let end_index = 5 + dl;
+        if end_index >= packet_length { return Err(ProtocolError::Incomplete); }
+        if buffer[end_index] != PACKET_END { return Err(ProtocolError::BadEndMarker); }
+        let data = buffer[5..end_index].to_vec();
+        Ok((Self{zone, cc, ac, data}, end_index + 1))
+    }
+}
+
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ds = f.debug_struct("Response");
+        ds.field("zone", &self.zone);
+        ds.field("cc", &self.cc);
+        ds.field("ac", &self.ac);
+        ds.field("data", &self.data);
+        if self.cc == Command::SimulateRC5IRCommand {
+            if let Ok(aliases) = RC5Aliases::try_from(&self.data[..]) {
+                ds.field("rc5command", &aliases);
+            }
+        } else if self.data.len() == 1 && self.data[0] == REQUEST_QUERY {
+            ds.field("value", &"RequestQuery");
+        } else if self.cc == Command::RequestCurrentSource && self.data.len() == 1 {
+            if let Some(value) = Source::from_u8(self.data[0]) {
+                ds.field("value", &value);
+            }
+        }
+        ds.finish()
+    }
+}
+
+/// The command name used in the text notation for `SimulateRC5IRCommand`.
+static RC5_NOTATION: &str = "RC5";
+
+/// The separator of the command and the answer code of a response in the text notation.
+static ANSWER_NOTATION: &str = "->";
+
+/// Is the data of the command text, written as a quoted string in the text notation.
+fn is_text_command(cc: Command) -> bool {
+    matches!(
+        cc,
+        Command::FMGenre
+        | Command::RequestRDSInformation
+        | Command::RequestDABStation
+        | Command::ProgrammeTypeCategory
+        | Command::DLSPDTInformation
+        | Command::SetRequestInputName
+    )
+}
+
+/// The name of a single byte value of the command in the text notation, if it has one.
+fn value_name(cc: Command, value: u8) -> Option<String> {
+    match cc {
+        Command::Power => PowerState::from_u8(value).map(|v| v.to_string()),
+        Command::DisplayBrightness => Brightness::from_u8(value).map(|v| v.to_string()),
+        Command::RequestMuteStatus => MuteState::from_u8(value).map(|v| v.to_string()),
+        Command::RequestCurrentSource => Source::from_u8(value).map(|v| v.to_string()),
+        _ => None,
+    }
+}
+
+/// The data bytes for a value name of the command in the text notation, if it is one.
+fn parse_value_name(cc: Command, name: &str) -> Option<Vec<u8>> {
+    match cc {
+        Command::Power => PowerState::from_str(name).ok().map(|v| vec![v as u8]),
+        Command::DisplayBrightness => Brightness::from_str(name).ok().map(|v| vec![v as u8]),
+        Command::RequestMuteStatus => MuteState::from_str(name).ok().map(|v| vec![v as u8]),
+        Command::RequestCurrentSource => Source::from_str(name).ok().map(|v| vec![v as u8]),
+        Command::SimulateRC5IRCommand => RC5_DATA.iter()
+            .find(|(rc5command, _)| format!("{:?}", rc5command) == name)
+            .map(|(_, (system, command))| vec![*system, *command]),
+        _ => None,
+    }
+}
+
+/// Write the command name of the text notation.
+fn write_notation_command(f: &mut fmt::Formatter<'_>, cc: Command) -> fmt::Result {
+    if cc == Command::SimulateRC5IRCommand { write!(f, "{}", RC5_NOTATION) } else { write!(f, "{:?}", cc) }
+}
+
+/// Write the data of the text notation, each item preceded by a space.
+///
+/// An RC5 code is written as the name of the primary [RC5Command](enum.RC5Command.html), a
+/// single byte value as its name if the command has named values, text as a quoted string
+/// with escapes for `"`, `\` and non-printable bytes, and anything else as decimal bytes.
+fn write_notation_data(f: &mut fmt::Formatter<'_>, cc: Command, data: &[u8]) -> fmt::Result {
+    if cc == Command::SimulateRC5IRCommand {
+        if let Ok(aliases) = RC5Aliases::try_from(data) {
+            return write!(f, " {:?}", aliases.primary());
+        }
+    }
+    if data.len() == 1 {
+        if let Some(name) = value_name(cc, data[0]) {
+            return write!(f, " {}", name);
+        }
+    }
+    if is_text_command(cc) && !data.is_empty() {
+        let mut text = String::from("\"");
+        for b in data {
+            match b {
+                b'"' | b'\\' => { text.push('\\'); text.push(*b as char); },
+                0x20..=0x7e => text.push(*b as char),
+                _ => text.push_str(&format!("\\x{:02x}", b)),
+            }
+        }
+        text.push('"');
+        return write!(f, " {}", text);
+    }
+    data.iter().try_for_each(|b| write!(f, " {}", b))
+}
+
+/// Split text notation into tokens, separated by whitespace. Quoted strings are single tokens.
+fn notation_tokens(s: &str) -> Result<Vec<&str>, ProtocolError> {
+    let mut tokens = vec![];
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let end = if rest.starts_with('"') {
+            let mut escaped = false;
+            rest.char_indices().skip(1)
+                .find(|(_, c)| {
+                    let closing = !escaped && *c == '"';
+                    escaped = !escaped && *c == '\\';
+                    closing
+                })
+                .map(|(i, _)| i + 1)
+                .ok_or(ProtocolError::BadNotation)?
+        } else {
+            rest.find(char::is_whitespace).unwrap_or(rest.len())
+        };
+        tokens.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Get the bytes of a quoted string token of the text notation.
+fn parse_notation_text(token: &str) -> Result<Vec<u8>, ProtocolError> {
+    let bytes = token.as_bytes();
+    if bytes.len() < 2 || bytes[0] != b'"' || bytes[bytes.len() - 1] != b'"' { return Err(ProtocolError::BadNotation); }
+    let text = &bytes[1..bytes.len() - 1];
+    let mut data = vec![];
+    let mut i = 0;
+    while i < text.len() {
+        match text[i] {
+            b'\\' if text.get(i + 1) == Some(&b'x') => {
+                let digits = text.get(i + 2..i + 4).ok_or(ProtocolError::BadNotation)?;
+                let digits = core::str::from_utf8(digits).map_err(|_| ProtocolError::BadNotation)?;
+                data.push(u8::from_str_radix(digits, 16).map_err(|_| ProtocolError::BadNotation)?);
+                i += 4;
+            },
+            b'\\' => {
+                data.push(*text.get(i + 1).ok_or(ProtocolError::BadNotation)?);
+                i += 2;
+            },
+            b => {
+                data.push(b);
+                i += 1;
+            },
+        }
+    }
+    Ok(data)
+}
+
+/// Get the [ZoneNumber](enum.ZoneNumber.html) from a `Z1` or `Z2` token of the text notation.
+fn parse_notation_zone(token: &str) -> Result<ZoneNumber, ProtocolError> {
+    token.strip_prefix('Z')
+        .and_then(|n| n.parse::<u8>().ok())
+        .and_then(ZoneNumber::from_u8)
+        .ok_or(ProtocolError::BadNotation)
+}
+
+/// Get the [Command](enum.Command.html) from a command token of the text notation, along
+/// with whether it has the `?` suffix marking a query. The names of all dialects are
+/// accepted.
+fn parse_notation_command(token: &str) -> Result<(Command, bool), ProtocolError> {
+    let (name, query) = match token.strip_suffix('?') {
+        Some(name) => (name, true),
+        None => (token, false),
+    };
+    let cc = if name == RC5_NOTATION {
+        Some(Command::SimulateRC5IRCommand)
+    } else {
+        Dialect::AVR850.parse_command_name(name).or_else(|| Dialect::AVR600.parse_command_name(name))
+    };
+    cc.map(|cc| (cc, query)).ok_or(ProtocolError::BadNotation)
+}
+
+/// Get the data from the data tokens of the text notation.
+fn parse_notation_data(cc: Command, tokens: &[&str]) -> Result<Vec<u8>, ProtocolError> {
+    let mut data = vec![];
+    for token in tokens {
+        if token.starts_with('"') {
+            data.extend(parse_notation_text(token)?);
+        } else if let Ok(value) = token.parse::<u8>() {
+            data.push(value);
+        } else {
+            data.extend(parse_value_name(cc, token).ok_or(ProtocolError::BadNotation)?);
+        }
+    }
+    Ok(data)
+}
+
+/// The text notation of a request, e.g. `Z1 SetRequestVolume 20`, `Z2 RC5 Zone2CD`, or
+/// `Z1 Power?` for a query.
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Z{} ", self.zone as u8)?;
+        write_notation_command(f, self.cc)?;
+        if self.data.len() == 1 && self.data[0] == REQUEST_QUERY {
+            write!(f, "?")
+        } else {
+            write_notation_data(f, self.cc, &self.data)
+        }
+    }
+}
+
+impl FromStr for Request {
+    type Err = ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = notation_tokens(s)?;
+        if tokens.len() < 2 { return Err(ProtocolError::BadNotation); }
+        let zone = parse_notation_zone(tokens[0])?;
+        let (cc, query) = parse_notation_command(tokens[1])?;
+        let data = if query {
+            if tokens.len() > 2 { return Err(ProtocolError::BadNotation); }
+            vec![REQUEST_QUERY]
+        } else {
+            parse_notation_data(cc, &tokens[2..])?
+        };
+        Self::new(zone, cc, data)
+    }
+}
+
+/// The text notation of a response, e.g. `Z1 Power -> StatusUpdate On`. When parsing, the
+/// command may have a `?` suffix, as in `Z1 Power? -> StatusUpdate On`, to show the response
+/// is the answer to a query; it makes no difference to the response.
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Z{} ", self.zone as u8)?;
+        write_notation_command(f, self.cc)?;
+        write!(f, " {} {}", ANSWER_NOTATION, self.ac)?;
+        write_notation_data(f, self.cc, &self.data)
+    }
+}
+
+impl FromStr for Response {
+    type Err = ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = notation_tokens(s)?;
+        if tokens.len() < 4 || tokens[2] != ANSWER_NOTATION { return Err(ProtocolError::BadNotation); }
+        let zone = parse_notation_zone(tokens[0])?;
+        let (cc, _) = parse_notation_command(tokens[1])?;
+        let ac = AnswerCode::from_str(tokens[3]).map_err(|_| ProtocolError::BadNotation)?;
+        let data = parse_notation_data(cc, &tokens[4..])?;
+        Self::new(zone, cc, ac, data)
+    }
+}
+
+/// The decoded meaning of a status update [Response](struct.Response.html) from the amplifier.
+///
+/// There is a variant for each [Command](enum.Command.html) an amplifier can report on, with
+/// the values decoded into typed values.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AmpEvent {
+    // =================== System Commands
+    Power { zone: ZoneNumber, state: PowerState },
+    DisplayBrightness(Brightness),
+    Headphones { connected: bool },
+    FMGenre { zone: ZoneNumber, genre: String },
+    SoftwareVersion(SoftwareVersion),
+    RestoreFactoryDefaultSettings,
+    SaveRestoreSecureCopyOfSettings,
+    RC5Command { zone: ZoneNumber, command: RC5Aliases },
+    /// The number of the information panel shown on the front panel display. This stays a
+    /// number: which panels there are, and in which order, depends on the source selected, so
+    /// the numbers have no fixed meaning to decode to.
+    DisplayInformationType { zone: ZoneNumber, panel: u8 },
+    CurrentSource { zone: ZoneNumber, source: Source },
+    HeadphoneOverride { active: bool },
+    // =================== Input Commands
+    VideoSelection { zone: ZoneNumber, source: VideoSource },
+    AudioInputType { zone: ZoneNumber, input: AudioInputType },
+    // =================== Output Commands
+    Volume { zone: ZoneNumber, level: Volume },
+    Mute { zone: ZoneNumber, state: MuteState },
+    DirectMode { zone: ZoneNumber, on: bool },
+    DecodeMode2ch(DecodeMode2ch),
+    DecodeModeMCH(DecodeModeMCH),
+    RDSInformation { zone: ZoneNumber, text: String },
+    VideoOutputResolution(VideoOutputResolution),
+    // =================== Menu Commands
+    MenuStatus(MenuStatus),
+    TunerPreset { zone: ZoneNumber, preset: Option<u8> },
+    Tune { zone: ZoneNumber, megahertz: u8, ten_kilohertz: u8 },
+    DABStation { zone: ZoneNumber, name: String },
+    ProgrammeType { zone: ZoneNumber, category: String },
+    DlsPdt { zone: ZoneNumber, text: String },
+    PresetDetails { zone: ZoneNumber, preset: u8, station: PresetStation },
+    NetworkPlaybackStatus { zone: ZoneNumber, status: NetworkPlaybackStatus },
+    IMAXEnhanced(IMAXEnhancedMode),
+    // =================== Setup Adjustment Commands
+    TrebleEqualisation(DecibelOffset),
+    BassEqualisation(DecibelOffset),
+    RoomEqualisation(RoomEqualisation),
+    DolbyVolume { on: bool },
+    DolbyLeveller(Option<u8>),
+    DolbyVolumeCalibrationOffset(CalibrationOffset),
+    Balance(DecibelOffset),
+    DolbyProLogicIIDimension(Dimension),
+    DolbyProLogicIICentreWidth(CentreWidth),
+    DolbyProLogicIIPanorama { on: bool },
+    SubwooferTrim(HalfDecibelOffset),
+    LipsyncDelay(LipsyncDelay),
+    Compression(Compression),
+    IncomingVideoParameters(VideoParameters),
+    IncomingAudioFormat { format: AudioFormat, channels: ChannelConfiguration },
+    IncomingAudioSampleRate(SampleRate),
+    SubStereoTrim(HalfDecibelOffset),
+    Zone1OSD { on: bool },
+    VideoOutputSwitching(VideoOutput),
+    InputName { zone: ZoneNumber, name: String },
+    FMScan { zone: ZoneNumber },
+    DABScan { zone: ZoneNumber },
+    Heartbeat,
+    Reboot,
+    // AVR600 only.
+    VideoInputType(VideoInputType),
+    PictureBrightness(PictureAdjustment),
+    PictureContrast(PictureAdjustment),
+    PictureColour(PictureAdjustment),
+    PictureMode(PictureMode),
+    EdgeEnhancement(FilterLevel),
+    MosquitoNoiseReduction(FilterLevel),
+    NoiseReduction(FilterLevel),
+    BlockNoiseReduction(FilterLevel),
+    OutputFrameRate(OutputFrameRate),
+}
+
+/// Check that the data of a [Response](struct.Response.html) is the expected length.
+fn check_length(response: &Response, length: usize) -> Result<(), ProtocolError> {
+    if response.data.len() == length { Ok(()) }
+    else { Err(ProtocolError::BadDataLength(response.cc, response.data.len())) }
+}
+
+/// Get the single byte of data of a [Response](struct.Response.html).
+fn single_value(response: &Response) -> Result<u8, ProtocolError> {
+    check_length(response, 1)?;
+    Ok(response.data[0])
+}
+
+/// Get the single byte of data of a [Response](struct.Response.html) as a value of an enum.
+fn enum_value<T: FromPrimitive>(response: &Response) -> Result<T, ProtocolError> {
+    let value = single_value(response)?;
+    T::from_u8(value).ok_or(ProtocolError::BadValue(response.cc, value))
+}
+
+/// Get the single byte of data of a [Response](struct.Response.html) as a value decoded by
+/// the given function.
+fn decoded_value<T>(response: &Response, decode: fn(u8) -> Option<T>) -> Result<T, ProtocolError> {
+    let value = single_value(response)?;
+    decode(value).ok_or(ProtocolError::BadValue(response.cc, value))
+}
+
+/// Get the single byte of data of a [Response](struct.Response.html) as an off/on flag.
+fn flag_value(response: &Response) -> Result<bool, ProtocolError> {
+    match single_value(response)? {
+        0x00 => Ok(false),
+        0x01 => Ok(true),
+        x => Err(ProtocolError::BadValue(response.cc, x)),
+    }
+}
+
+/// Get the data of a [Response](struct.Response.html) as a string. The string may be nul
+/// terminated and is padded with spaces.
+fn text_value(response: &Response) -> String {
+    decode_broadcast_text(&response.data)
+}
+
+impl TryFrom<&Response> for AmpEvent {
+    type Error = ProtocolError;
+
+    fn try_from(response: &Response) -> Result<Self, Self::Error> {
+        if response.ac != AnswerCode::StatusUpdate {
+            return Err(ProtocolError::ErrorAnswer(response.cc, response.ac));
+        }
+        let zone = response.zone;
+        Ok(match response.cc {
+            Command::Power => Self::Power { zone, state: enum_value(response)? },
+            Command::DisplayBrightness => Self::DisplayBrightness(enum_value(response)?),
+            Command::Headphones => Self::Headphones { connected: flag_value(response)? },
+            Command::FMGenre => Self::FMGenre { zone, genre: text_value(response) },
+            Command::SoftwareVersion => {
+                check_length(response, 3)?;
+                let component = SoftwareComponent::decode(response.data[0]).ok_or(ProtocolError::BadValue(response.cc, response.data[0]))?;
+                Self::SoftwareVersion(SoftwareVersion { component, major: response.data[1], minor: response.data[2] })
+            },
+            Command::RestoreFactoryDefaultSettings => Self::RestoreFactoryDefaultSettings,
+            Command::SaveRestoreSecureCopyOfSettings => Self::SaveRestoreSecureCopyOfSettings,
+            Command::SimulateRC5IRCommand => {
+                // Responses to this Request Command provide no data on the state of the
+                // amplifier, they just give the AnswerCode to the Request.
+                Self::RC5Command { zone, command: RC5Aliases::try_from(&response.data[..])? }
+            },
+            Command::DisplayInformationType => Self::DisplayInformationType { zone, panel: single_value(response)? },
+            Command::RequestCurrentSource => Self::CurrentSource { zone, source: enum_value(response)? },
+            Command::HeadphoneOverride => Self::HeadphoneOverride { active: flag_value(response)? },
+            Command::VideoSelection => Self::VideoSelection { zone, source: enum_value(response)? },
+            Command::SelectAnalogueDigital => Self::AudioInputType { zone, input: enum_value(response)? },
+            Command::SetRequestVolume => Self::Volume { zone, level: decoded_value(response, Volume::decode)? },
+            Command::RequestMuteStatus => Self::Mute { zone, state: enum_value(response)? },
+            Command::RequestDirectModeStatus => Self::DirectMode { zone, on: flag_value(response)? },
+            Command::RequestDecodeModeStatus2ch => Self::DecodeMode2ch(enum_value(response)?),
+            Command::RequestDecodeModeStatusMCH => Self::DecodeModeMCH(enum_value(response)?),
+            Command::RequestRDSInformation => Self::RDSInformation { zone, text: text_value(response) },
+            Command::SetRequestVideoOutputResolution => Self::VideoOutputResolution(enum_value(response)?),
+            Command::RequestMenuStatus => Self::MenuStatus(enum_value(response)?),
+            Command::RequestTunerPreset => {
+                let preset = single_value(response)?;
+                Self::TunerPreset { zone, preset: if preset == 0xff { None } else { Some(preset) } }
+            },
+            Command::Tune => {
+                check_length(response, 2)?;
+                Self::Tune { zone, megahertz: response.data[0], ten_kilohertz: response.data[1] }
+            },
+            Command::RequestDABStation => {
+                // Station name is always 16 bytes long.
+                check_length(response, 16)?;
+                Self::DABStation { zone, name: text_value(response) }
+            },
+            Command::ProgrammeTypeCategory => {
+                // Programme type is always 16 bytes long.
+                check_length(response, 16)?;
+                Self::ProgrammeType { zone, category: text_value(response) }
+            },
+            Command::DLSPDTInformation => {
+                // An AVR850 appears to behave differently to the documentation. Documentation
+                // says a 128 byte buffer with the string padded out with spaces. Reality indicates
+                // a 129 byte buffer with a nul terminated string padded out with spaces. Accept
+                // both.
+                if response.data.len() != 128 && response.data.len() != 129 {
+                    return Err(ProtocolError::BadDataLength(response.cc, response.data.len()));
+                }
+                Self::DlsPdt { zone, text: text_value(response) }
+            },
+            Command::RequestPresetDetails => {
+                let (preset, station) = PresetStation::decode(&response.data)?;
+                Self::PresetDetails { zone, preset, station }
+            },
+            Command::NetworkPlaybackStatus => Self::NetworkPlaybackStatus { zone, status: enum_value(response)? },
+            Command::IMAXEnhanced => Self::IMAXEnhanced(enum_value(response)?),
+            Command::TrebleEqualisation => Self::TrebleEqualisation(decoded_value(response, DecibelOffset::decode)?),
+            Command::BassEqualisation => Self::BassEqualisation(decoded_value(response, DecibelOffset::decode)?),
+            Command::RoomEqualisation => Self::RoomEqualisation(enum_value(response)?),
+            Command::DolbyVolume => Self::DolbyVolume { on: flag_value(response)? },
+            Command::DolbyLeveller => {
+                let level = single_value(response)?;
+                Self::DolbyLeveller(if level == 0xff { None } else { Some(level) })
+            },
+            Command::DolbyVolumeCalibrationOffset => Self::DolbyVolumeCalibrationOffset(decoded_value(response, CalibrationOffset::decode)?),
+            Command::Balance => Self::Balance(decoded_value(response, DecibelOffset::decode)?),
+            Command::DolbyProLogicIIDimension => Self::DolbyProLogicIIDimension(decoded_value(response, Dimension::decode)?),
+            Command::DolbyProLogicIICentreWidth => Self::DolbyProLogicIICentreWidth(decoded_value(response, CentreWidth::decode)?),
+            Command::DolbyProLogicIIPanorama => Self::DolbyProLogicIIPanorama { on: flag_value(response)? },
+            Command::SubwooferTrim => Self::SubwooferTrim(decoded_value(response, HalfDecibelOffset::decode)?),
+            Command::LipsyncDelay => Self::LipsyncDelay(decoded_value(response, LipsyncDelay::decode)?),
+            Command::Compression => Self::Compression(enum_value(response)?),
+            Command::RequestIncomingVideoParameters => Self::IncomingVideoParameters(VideoParameters::decode(&response.data)?),
+            Command::RequestIncomingAudioFormat => {
+                check_length(response, 2)?;
+                let format = AudioFormat::from_u8(response.data[0]).ok_or(ProtocolError::BadValue(response.cc, response.data[0]))?;
+                let channels = ChannelConfiguration::decode(response.data[1]).ok_or(ProtocolError::BadValue(response.cc, response.data[1]))?;
+                Self::IncomingAudioFormat { format, channels }
+            },
+            Command::RequestIncomingAudioSampleRate => Self::IncomingAudioSampleRate(enum_value(response)?),
+            Command::SetRequestSubStereoTrim => Self::SubStereoTrim(decoded_value(response, HalfDecibelOffset::decode)?),
+            Command::SetRequestZone1OSDOnOff => Self::Zone1OSD { on: flag_value(response)? },
+            Command::SetRequestVideoOutputSwitching => Self::VideoOutputSwitching(enum_value(response)?),
+            Command::SetRequestInputName => Self::InputName { zone, name: text_value(response) },
+            Command::FMScanUpDown => Self::FMScan { zone },
+            Command::DABScan => Self::DABScan { zone },
+            Command::Heartbeat => Self::Heartbeat,
+            Command::Reboot => Self::Reboot,
+            Command::SetRequestVideoInputType => Self::VideoInputType(enum_value(response)?),
+            Command::SetRequestBrightness => Self::PictureBrightness(decoded_value(response, PictureAdjustment::decode)?),
+            Command::SetRequestContrast => Self::PictureContrast(decoded_value(response, PictureAdjustment::decode)?),
+            Command::SetRequestColour => Self::PictureColour(decoded_value(response, PictureAdjustment::decode)?),
+            Command::SetRequestPictureMode => Self::PictureMode(enum_value(response)?),
+            Command::SetRequestEdgeEnhancement => Self::EdgeEnhancement(enum_value(response)?),
+            Command::SetRequestMosquitoNR => Self::MosquitoNoiseReduction(enum_value(response)?),
+            Command::SetRequestNoiseReduction => Self::NoiseReduction(enum_value(response)?),
+            Command::SetRequestBlockNoiseReduction => Self::BlockNoiseReduction(enum_value(response)?),
+            Command::SetRequestOutputFrameRate => Self::OutputFrameRate(enum_value(response)?),
+        })
+    }
+}
+
+/// A high level command to the amplifier.
+///
+/// Each command is turned into the [Request](struct.Request.html)s to send to the amplifier
+/// by [to_requests](enum.AmpCommand.html#method.to_requests). This deals with whether to use
+/// a native [Command](enum.Command.html) or a `SimulateRC5IRCommand` with the right
+/// [RC5Command](enum.RC5Command.html) for the zone. `SimulateRC5IRCommand` requests do not
+/// respond with the changed status, so they are followed by a query of the changed status.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AmpCommand {
+    /// Ask for the current value associated with a [Command](enum.Command.html).
+    Query(ZoneNumber, Command),
+    /// Ask for the version of a [SoftwareComponent](enum.SoftwareComponent.html).
+    QuerySoftwareVersion(SoftwareComponent),
+    SetPower(ZoneNumber, PowerState),
+    SetVolume(ZoneNumber, Volume),
+    SetMute(ZoneNumber, MuteState),
+    SelectSource(ZoneNumber, Source),
+    SetBrightness(Brightness),
+    SetBass(DecibelOffset),
+    SetTreble(DecibelOffset),
+    /// Send an arbitrary [RC5Command](enum.RC5Command.html).
+    RC5(ZoneNumber, RC5Command),
+}
+
+/// The [RC5Command](enum.RC5Command.html) that selects a [Source](enum.Source.html) for a zone.
+fn source_rc5command(zone: ZoneNumber, source: Source) -> Result<RC5Command, ProtocolError> {
+    let rc5_command = match zone {
+        ZoneNumber::One => match source {
+            Source::FollowZone1 => None,
+            Source::CD => Some(RC5Command::CD),
+            Source::BD => Some(RC5Command::BD),
+            Source::AV => Some(RC5Command::AV),
+            Source::SAT => Some(RC5Command::Sat),
+            Source::PVR => Some(RC5Command::PVR),
+            Source::VCR => Some(RC5Command::VCR),
+            Source::AUX => Some(RC5Command::Aux),
+            Source::DISPLAY => Some(RC5Command::Display),
+            Source::TUNER => Some(RC5Command::Radio),
+            Source::TUNERDAB => Some(RC5Command::DAB),
+            Source::NET => Some(RC5Command::Net),
+            Source::USB => Some(RC5Command::USB),
+            Source::STB => Some(RC5Command::STB),
+            Source::GAME => Some(RC5Command::Game),
+        },
+        ZoneNumber::Two => match source {
+            Source::FollowZone1 => Some(RC5Command::SetZone2ToFollowZone1),
+            Source::CD => Some(RC5Command::Zone2CD),
+            Source::BD => Some(RC5Command::Zone2BD),
+            Source::AV => Some(RC5Command::Zone2AV),
+            Source::SAT => Some(RC5Command::Zone2Sat),
+            Source::PVR => Some(RC5Command::Zone2PVR),
+            Source::VCR => Some(RC5Command::Zone2VCR),
+            Source::AUX => Some(RC5Command::Zone2Aux),
+            Source::DISPLAY => None,
+            Source::TUNER => Some(RC5Command::Zone2FM),
+            Source::TUNERDAB => Some(RC5Command::Zone2DAB),
+            Source::NET => Some(RC5Command::Zone2NET),
+            Source::USB => Some(RC5Command::Zone2USB),
+            Source::STB => Some(RC5Command::Zone2STB),
+            Source::GAME => Some(RC5Command::Zone2Game),
+        },
+    };
+    rc5_command.ok_or(ProtocolError::SourceNotForZone(source, zone))
+}
+
+/// Create a `SimulateRC5IRCommand` [Request](struct.Request.html).
+fn rc5_request(zone: ZoneNumber, rc5_command: RC5Command) -> Result<Request, ProtocolError> {
+    let rc5_data = get_rc5command_data(rc5_command);
+    Request::new(zone, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1])
+}
+
+/// Create a [Request](struct.Request.html) for the current value associated with a
+/// [Command](enum.Command.html).
+fn query_request(zone: ZoneNumber, cc: Command) -> Result<Request, ProtocolError> {
+    Request::new(zone, cc, vec![REQUEST_QUERY])
+}
+
+impl AmpCommand {
+    /// Return the sequence of [Request](struct.Request.html)s that carry out this command.
+    pub fn to_requests(self: &Self) -> Result<Vec<Request>, ProtocolError> {
+        Ok(match *self {
+            Self::Query(zone, cc) => vec![query_request(zone, cc)?],
+            Self::QuerySoftwareVersion(component) => {
+                vec![Request::new(ZoneNumber::One, Command::SoftwareVersion, vec![component.encode()])?]
+            },
+            Self::SetPower(zone, power) => {
+                let rc5_command = match (zone, power) {
+                    (ZoneNumber::One, PowerState::On) => RC5Command::PowerOn,
+                    (ZoneNumber::One, PowerState::Standby) => RC5Command::PowerOff,
+                    (ZoneNumber::Two, PowerState::On) => RC5Command::Zone2PowerOn,
+                    (ZoneNumber::Two, PowerState::Standby) => RC5Command::Zone2PowerOff,
+                };
+                vec![rc5_request(zone, rc5_command)?, query_request(zone, Command::Power)?]
+            },
+            Self::SetVolume(zone, volume) => {
+                vec![Request::new(zone, Command::SetRequestVolume, vec![volume.encode()])?]
+            },
+            Self::SetMute(zone, mute) => {
+                let rc5_command = match (zone, mute) {
+                    (ZoneNumber::One, MuteState::Muted) => RC5Command::MuteOn,
+                    (ZoneNumber::One, MuteState::NotMuted) => RC5Command::MuteOff,
+                    (ZoneNumber::Two, MuteState::Muted) => RC5Command::Zone2MuteOn,
+                    (ZoneNumber::Two, MuteState::NotMuted) => RC5Command::Zone2MuteOff,
+                };
+                vec![rc5_request(zone, rc5_command)?, query_request(zone, Command::RequestMuteStatus)?]
+            },
+            Self::SelectSource(zone, source) => {
+                vec![rc5_request(zone, source_rc5command(zone, source)?)?, query_request(zone, Command::RequestCurrentSource)?]
+            },
+            Self::SetBrightness(level) => {
+                let rc5_command = match level {
+                    Brightness::Off => RC5Command::DisplayOff,
+                    Brightness::Level1 => RC5Command::DisplayL1,
+                    Brightness::Level2 => RC5Command::DisplayL2,
+                };
+                vec![rc5_request(ZoneNumber::One, rc5_command)?, query_request(ZoneNumber::One, Command::DisplayBrightness)?]
+            },
+            Self::SetBass(value) => {
+                vec![Request::new(ZoneNumber::One, Command::BassEqualisation, vec![value.encode()])?]
+            },
+            Self::SetTreble(value) => {
+                vec![Request::new(ZoneNumber::One, Command::TrebleEqualisation, vec![value.encode()])?]
+            },
+            Self::RC5(zone, rc5_command) => vec![rc5_request(zone, rc5_command)?],
+        })
+    }
+}
+
+/// The largest number of unanswered [Request](struct.Request.html)s remembered by
+/// [PendingRequests](struct.PendingRequests.html).
+pub const MAX_PENDING_REQUESTS: usize = 64;
+
+/// What a [Request](struct.Request.html) asks the amplifier to do, in words that can follow
+/// "cannot", e.g. "change source".
+fn request_action(request: &Request) -> String {
+    if request.cc == Command::SimulateRC5IRCommand {
+        if let Ok(aliases) = RC5Aliases::try_from(&request.data[..]) {
+            return rc5command_action(request.zone, aliases.primary());
+        }
+    }
+    let subject = match request.cc {
+        Command::Power => "power state",
+        Command::DisplayBrightness => "display brightness",
+        Command::SoftwareVersion => "software version",
+        Command::RequestCurrentSource | Command::VideoSelection => "source",
+        Command::SetRequestVolume => "volume",
+        Command::RequestMuteStatus => "mute state",
+        Command::TrebleEqualisation => "treble",
+        Command::BassEqualisation => "bass",
+        Command::Balance => "balance",
+        Command::RequestIncomingAudioFormat => "incoming audio format",
+        Command::RequestIncomingAudioSampleRate => "incoming audio sample rate",
+        Command::RequestIncomingVideoParameters => "incoming video parameters",
+        cc => return format!("carry out {:?}", cc),
+    };
+    format!("{} {}", if request.is_query() { "report" } else { "change" }, subject)
+}
+
+/// What an [RC5Command](enum.RC5Command.html) sent to a zone asks the amplifier to do, in words
+/// that can follow "cannot".
+fn rc5command_action(zone: ZoneNumber, rc5_command: RC5Command) -> String {
+    match rc5_command {
+        RC5Command::PowerOn | RC5Command::Zone2PowerOn => "power on".to_string(),
+        RC5Command::PowerOff | RC5Command::Zone2PowerOff => "go to standby".to_string(),
+        RC5Command::MuteOn | RC5Command::Zone2MuteOn => "mute".to_string(),
+        RC5Command::MuteOff | RC5Command::Zone2MuteOff => "unmute".to_string(),
+        RC5Command::DisplayOff | RC5Command::DisplayL1 | RC5Command::DisplayL2 => "change display brightness".to_string(),
+        _ if Source::ALL.iter().any(|source| source_rc5command(zone, *source) == Ok(rc5_command)) => "change source".to_string(),
+        _ => format!("carry out {:?}", rc5_command),
+    }
+}
+
+/// An error answer from the amplifier, tied back to the [Request](struct.Request.html) that
+/// caused it if that is known.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AnswerError {
+    pub zone: ZoneNumber,
+    pub cc: Command,
+    pub answer: ErrorAnswerCode,
+    pub request: Option<Request>,
+}
+
+impl AnswerError {
+    /// What the request asked the amplifier to do, in words that can follow "cannot", e.g.
+    /// "change source".
+    pub fn action(self: &Self) -> String {
+        match &self.request {
+            Some(request) => request_action(request),
+            None => format!("carry out {:?}", self.cc),
+        }
+    }
+}
+
+impl fmt::Display for AnswerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let zone = self.zone as u8;
+        let action = self.action();
+        match self.answer {
+            ErrorAnswerCode::ZoneInvalid => write!(f, "Zone {} cannot {}, the command is not valid for the zone.", zone, action),
+            ErrorAnswerCode::CommandNotRecognized => write!(f, "Zone {} cannot {}, the amplifier does not recognise the command.", zone, action),
+            ErrorAnswerCode::ParameterNotRecognized => write!(f, "Zone {} cannot {}, the amplifier does not recognise the value.", zone, action),
+            ErrorAnswerCode::CommandInvalidAtThisTime => write!(f, "Zone {} cannot {} at this time.", zone, action),
+            ErrorAnswerCode::InvalidDataLength => write!(f, "Zone {} cannot {}, the request has the wrong amount of data.", zone, action),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AnswerError {}
+
+/// The [Request](struct.Request.html)s sent to the amplifier that have not yet been answered,
+/// so that each [Response](struct.Response.html) can be tied back to the request that caused it.
+///
+/// A response answers the oldest outstanding request with the same zone and command, for
+/// `SimulateRC5IRCommand` preferring one with the same RC5 code. Responses that are status
+/// updates the amplifier sends unprompted answer nothing. Requests that are never answered are
+/// forgotten, oldest first, once there are more than
+/// [MAX_PENDING_REQUESTS](constant.MAX_PENDING_REQUESTS.html).
+#[derive(Clone, Debug, Default)]
+pub struct PendingRequests {
+    requests: VecDeque<Request>,
+}
+
+impl PendingRequests {
+    /// An empty record of requests.
+    pub fn new() -> Self { Self::default() }
+
+    /// Record that a request has been sent to the amplifier.
+    pub fn push(self: &mut Self, request: Request) {
+        if self.requests.len() == MAX_PENDING_REQUESTS { self.requests.pop_front(); }
+        self.requests.push_back(request);
+    }
+
+    /// The number of requests not yet answered.
+    pub fn len(self: &Self) -> usize { self.requests.len() }
+
+    /// Are all the requests answered.
+    pub fn is_empty(self: &Self) -> bool { self.requests.is_empty() }
+
+    /// Forget all the requests, e.g. on disconnection.
+    pub fn clear(self: &mut Self) { self.requests.clear(); }
+
+    /// Forget the oldest outstanding copy of a request, e.g. one that has gone unanswered for too
+    /// long, returning whether there was one to forget.
+    pub fn forget(self: &mut Self, request: &Request) -> bool {
+        match self.requests.iter().position(|r| r == request) {
+            Some(index) => { self.requests.remove(index); true },
+            None => false,
+        }
+    }
+
+    /// Remove and return the request answered by a response, or if the response is an error
+    /// answer return the [AnswerError](struct.AnswerError.html) tied to the request.
+    pub fn answer(self: &mut Self, response: &Response) -> Result<Option<Request>, AnswerError> {
+        let same_command = |r: &Request| r.zone == response.zone && r.cc == response.cc;
+        let index = self.requests.iter()
+            .position(|r| same_command(r) && (r.cc != Command::SimulateRC5IRCommand || r.data == response.data))
+            .or_else(|| self.requests.iter().position(same_command));
+        let request = index.and_then(|i| self.requests.remove(i));
+        match response.ac.error() {
+            None => Ok(request),
+            Some(answer) => Err(AnswerError { zone: response.zone, cc: response.cc, answer, request }),
+        }
+    }
+}
+
+/// The value that starts an AMX identification message.
+pub static AMX_START: &[u8] = b"AMX";
+
+/// The longest AMX identification message that will be accepted, excluding the terminating
+/// [PACKET_END](static.PACKET_END.html).
+const MAX_AMX_LENGTH: usize = 255;
+
+/// The start of the AMX identification message sent by an amplifier.
+static AMX_REPLY_START: &str = "AMXB";
+
+/// Create the bytes of an AMX identification request, the reply to which can be parsed into
+/// a [DeviceInfo](struct.DeviceInfo.html).
+pub fn create_amx_request() -> Vec<u8> {
+    let mut request = AMX_START.to_vec();
+    request.push(PACKET_END);
+    request
+}
+
+/// The identity of an amplifier as given in an AMX identification reply, for example
+/// "AMXB<Device-SDKClass=Receiver><Device-Make=ARCAM><Device-Model=AVR850><Device-Revision=2.0.0>".
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeviceInfo {
+    pub sdk_class: String,
+    pub make: String,
+    pub model: String,
+    pub revision: String,
+}
+
+impl FromStr for DeviceInfo {
+    type Err = ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let message = s.trim_end_matches(PACKET_END as char);
+        if !message.starts_with(AMX_REPLY_START) { return Err(ProtocolError::BadAmxMessage); }
+        let mut fields = &message[AMX_REPLY_START.len()..];
+        let (mut sdk_class, mut make, mut model, mut revision) = (None, None, None, None);
+        while !fields.is_empty() {
+            if !fields.starts_with('<') { return Err(ProtocolError::BadAmxMessage); }
+            let end = fields.find('>').ok_or(ProtocolError::BadAmxMessage)?;
+            let mut key_value = fields[1..end].splitn(2, '=');
+            let key = key_value.next().unwrap_or_default();
+            let value = key_value.next().ok_or(ProtocolError::BadAmxMessage)?.to_string();
+            match key {
+                "Device-SDKClass" => sdk_class = Some(value),
+                "Device-Make" => make = Some(value),
+                "Device-Model" => model = Some(value),
+                "Device-Revision" => revision = Some(value),
+                _ => {},
+            }
+            fields = &fields[end + 1..];
+        }
+        match (sdk_class, make, model, revision) {
+            (Some(sdk_class), Some(make), Some(model), Some(revision)) => Ok(DeviceInfo { sdk_class, make, model, revision }),
+            _ => Err(ProtocolError::BadAmxMessage),
+        }
+    }
+}
+
+impl fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.make, self.model, self.revision)
+    }
+}
+
+/// The packet types that a [PacketDecoder](struct.PacketDecoder.html) can extract from a
+/// byte stream.
+pub trait Packet: Sized {
+    /// The number of bytes preceding the data, the last of which is the data length.
+    const HEADER_LENGTH: usize;
+
+    /// Parse the bytes in the buffer to create a tuple representing a packet and the number
+    /// of bytes used for the packet.
+    fn parse_bytes(buffer: &[u8], dialect: Dialect) -> Result<(Self, usize), ProtocolError>;
+}
+
+impl Packet for Request {
+    const HEADER_LENGTH: usize = 4;
+
+    fn parse_bytes(buffer: &[u8], dialect: Dialect) -> Result<(Self, usize), ProtocolError> {
+        Request::parse_bytes_in_dialect(buffer, dialect)
+    }
+}
+
+impl Packet for Response {
+    const HEADER_LENGTH: usize = 5;
+
+    fn parse_bytes(buffer: &[u8], dialect: Dialect) -> Result<(Self, usize), ProtocolError> {
+        Response::parse_bytes_in_dialect(buffer, dialect)
+    }
+}
+
+/// The items that a [PacketDecoder](struct.PacketDecoder.html) extracts from a byte stream.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Frame<P> {
+    /// A complete Arcam protocol packet.
+    Packet(P),
+    /// An AMX identification message, for example "AMX" from a client or "AMXB<…>" from an
+    /// amplifier, without the terminating \r.
+    Amx(String),
+    /// Bytes that could not be made into a frame and so were discarded.
+    Skipped { count: usize, error: ProtocolError },
+}
+
+/// An incremental decoder for the byte stream from (or to) an amplifier.
+///
+/// TCP delivers bytes in chunks that bear no relation to the packet structure: a chunk may
+/// contain part of a packet, many packets, or AMX identification messages mixed in with
+/// packets. Chunks are given to the decoder using [push](struct.PacketDecoder.html#method.push)
+/// and complete [Frame](enum.Frame.html)s are then taken using
+/// [next_frame](struct.PacketDecoder.html#method.next_frame), or by iterating.
+///
+/// If the decoder finds bytes that cannot be the start of a frame it discards bytes up to the
+/// next [PACKET_START](static.PACKET_START.html) (or start of an AMX message) and reports how
+/// many bytes were discarded. Each byte is examined a bounded number of times, so decoding is
+/// linear in the length of the stream.
+#[derive(Debug)]
+pub struct PacketDecoder<P: Packet> {
+    buffer: Vec<u8>,
+    start: usize,
+    dialect: Dialect,
+    phantom: PhantomData<P>,
+}
+
+impl<P: Packet> Default for PacketDecoder<P> {
+    fn default() -> Self {
+        Self { buffer: vec![], start: 0, dialect: Dialect::default(), phantom: PhantomData }
+    }
+}
+
+impl<P: Packet> PacketDecoder<P> {
+    /// Create a new decoder with an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new decoder with an empty buffer for the given [Dialect](enum.Dialect.html)
+    /// of the protocol.
+    pub fn with_dialect(dialect: Dialect) -> Self {
+        Self { dialect, ..Self::default() }
+    }
+
+    /// Change the [Dialect](enum.Dialect.html) of the protocol used to parse packets.
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    /// Add a chunk of bytes received from the stream.
+    pub fn push(&mut self, bytes: &[u8]) {
+        // Only move the unprocessed bytes down when at least half the buffer is processed
+        // bytes, so that the cost of moving is amortised over the bytes processed.
+        if self.start == self.buffer.len() {
+            self.buffer.clear();
+            self.start = 0;
+        } else if self.start > self.buffer.len() / 2 {
+            self.buffer.drain(..self.start);
+            self.start = 0;
+        }
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// The number of bytes received but not yet made into a frame.
+    pub fn pending(&self) -> usize {
+        self.buffer.len() - self.start
+    }
+
+    /// Extract the next frame, if there is enough data to make one.
+    pub fn next_frame(&mut self) -> Option<Frame<P>> {
+        let data = &self.buffer[self.start..];
+        if data.is_empty() { return None; }
+        if data[0] == PACKET_START {
+            if data.len() < P::HEADER_LENGTH { return None; }
+            let frame_length = P::HEADER_LENGTH + data[P::HEADER_LENGTH - 1] as usize + 1;
+            if data.len() < frame_length { return None; }
+            if data[frame_length - 1] != PACKET_END {
+                return Some(self.skip(ProtocolError::BadEndMarker));
+            }
+            let result = P::parse_bytes(&data[..frame_length], self.dialect);
+            self.start += frame_length;
+            Some(match result {
+                Ok((packet, _)) => Frame::Packet(packet),
+                Err(error) => Frame::Skipped { count: frame_length, error },
+            })
+        } else if data.starts_with(AMX_START) {
+            match data.iter().take(MAX_AMX_LENGTH + 1).position(|x| *x == PACKET_END) {
+                Some(i) => {
+                    let message = String::from_utf8_lossy(&data[..i]).to_string();
+                    self.start += i + 1;
+                    Some(Frame::Amx(message))
+                },
+                None => if data.len() > MAX_AMX_LENGTH { Some(self.skip(ProtocolError::BadEndMarker)) } else { None },
+            }
+        } else if AMX_START.starts_with(data) {
+            // Could be the start of an AMX message, wait for more bytes.
+            None
+        } else {
+            Some(self.skip(ProtocolError::BadStartMarker))
+        }
+    }
+
+    /// Discard bytes up to the next possible start of a frame.
+    fn skip(&mut self, error: ProtocolError) -> Frame<P> {
+        let data = &self.buffer[self.start..];
+        let possible_start = |i: usize| {
+            data[i] == PACKET_START || AMX_START.starts_with(&data[i..data.len().min(i + AMX_START.len())])
+        };
+        let count = (1..data.len()).find(|i| possible_start(*i)).unwrap_or(data.len());
+        self.start += count;
+        Frame::Skipped { count, error }
+    }
+}
+
+impl<P: Packet> Iterator for PacketDecoder<P> {
+    type Item = Frame<P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_frame()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn create_display_brightness_request() {
+        let request = Request::new(ZoneNumber::One, Command::DisplayBrightness, vec![REQUEST_QUERY]).unwrap();
+        assert_eq!(
+            request.to_bytes(),
+            [PACKET_START, ZoneNumber::One as u8, Command::DisplayBrightness.code(), 0x01, REQUEST_QUERY, PACKET_END]
+        );
+    }
+
+    #[test]
+    fn create_get_volume_request() {
+        let request = Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![REQUEST_QUERY]).unwrap();
+        assert_eq!(
+            request.to_bytes(),
+            [PACKET_START, ZoneNumber::One as u8, Command::SetRequestVolume.code(), 0x01, REQUEST_QUERY, PACKET_END]
+        );
+    }
+
+    #[test]
+    fn create_set_volume_request() {
+        let request = Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![20]).unwrap();
+        assert_eq!(
+            request.to_bytes(),
+            [PACKET_START, ZoneNumber::One as u8, Command::SetRequestVolume.code(), 0x01, 0x14, PACKET_END]
+        );
+    }
+
+    #[test]
+    fn parse_empty_request_buffer() {
+        if let Err(e) = Request::parse_bytes(&[]) {
+            assert_eq!(e, ProtocolError::Incomplete);
+        };
+    }
+
+    #[test]
+    fn parse_request_buffer_with_incorrect_start_marker() {
+        if let Err(e) = Request::parse_bytes(&[21, 0, 0, 0, 0, 0]) {
+            assert_eq!(e, ProtocolError::BadStartMarker);
+        };
+    }
+
+    #[test]
+    fn parse_valid_set_volume_request() {
+        let request = Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![20]).unwrap();
+        assert_eq!(Request::parse_bytes(&request.to_bytes()).unwrap(), (request, 6));
+    }
+
+    #[test]
+    fn parse_buffer_with_multiple_request_packets() {
+        let r1 = Request::new(ZoneNumber::One, Command::RequestCurrentSource, vec![REQUEST_QUERY]).unwrap();
+        let r2 = Request::new(ZoneNumber::One, Command::DisplayBrightness, vec![REQUEST_QUERY]).unwrap();
+        let r3 = Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![30u8]).unwrap();
+        let mut input = r1.to_bytes();
+        input.append(&mut r2.to_bytes());
+        input.append(&mut r3.to_bytes());
+        assert_eq!(input, vec![
+            PACKET_START, ZoneNumber::One as u8, Command::RequestCurrentSource.code(), 1, REQUEST_QUERY, PACKET_END,
+            PACKET_START, ZoneNumber::One as u8, Command::DisplayBrightness.code(), 1, REQUEST_QUERY, PACKET_END,
+            PACKET_START, ZoneNumber::One as u8, Command::SetRequestVolume.code(), 1, 30, PACKET_END,
+        ]);
+        assert_eq!(
+            Request::parse_bytes(&input).unwrap(),
+            (r1, 6)
+        );
+    }
+
+    #[test]
+    fn cannot_create_zone_zero() {
+        let zone: Option<ZoneNumber> = FromPrimitive::from_u8(0);
+        assert!(zone.is_none())
+    }
+
+    #[test]
+    fn cannot_create_zone_four() {
+        let zone: Option<ZoneNumber> = FromPrimitive::from_u8(4);
+        assert!(zone.is_none())
+    }
+
+    #[test]
+    fn cannot_create_unknown_command() {
+        // We know that 0x61 is not known Command.
+        assert_eq!(Dialect::AVR850.command(0x61), Err(ProtocolError::UnknownCommand(0x61)));
+        assert_eq!(Dialect::AVR600.command(0x61), Err(ProtocolError::UnknownCommand(0x61)));
+    }
+
+    #[test]
+    fn data_length_must_be_less_than_256() {
+        assert_eq!(Request::new(ZoneNumber::One, Command::Power, vec![0u8; 300]), Err(ProtocolError::DataTooLong));
+        assert_eq!(Response::new(ZoneNumber::One, Command::Power, AnswerCode::StatusUpdate, vec![0u8; 300]), Err(ProtocolError::DataTooLong));
+    }
+
+    #[test]
+    fn parse_request_buffer_with_unknown_zone() {
+        assert_eq!(Request::parse_bytes(&[PACKET_START, 0x04, Command::Power.code(), 1, REQUEST_QUERY, PACKET_END]), Err(ProtocolError::UnknownZone(0x04)));
+    }
+
+    #[test]
+    fn parse_request_buffer_with_unknown_command() {
+        assert_eq!(Request::parse_bytes(&[PACKET_START, 0x01, 0x61, 1, REQUEST_QUERY, PACKET_END]), Err(ProtocolError::UnknownCommand(0x61)));
+    }
+
+    #[test]
+    fn parse_request_buffer_with_incorrect_end_marker() {
+        assert_eq!(Request::parse_bytes(&[PACKET_START, 0x01, Command::Power.code(), 1, REQUEST_QUERY, 0x00]), Err(ProtocolError::BadEndMarker));
+    }
+
+    #[test]
+    fn parse_incomplete_request_buffer() {
+        assert_eq!(Request::parse_bytes(&[PACKET_START, 0x01, Command::Power.code(), 1, REQUEST_QUERY]), Err(ProtocolError::Incomplete));
+    }
+
+    #[test]
+    fn create_display_brightness_response() {
+        let response = Response::new(ZoneNumber::One, Command::DisplayBrightness, AnswerCode::StatusUpdate, vec![Brightness::Level1 as u8]).unwrap();
+        assert_eq!(
+            response.to_bytes(),
+            [PACKET_START, ZoneNumber::One as u8, Command::DisplayBrightness.code(), AnswerCode::StatusUpdate as u8, 0x01, Brightness::Level1 as u8, PACKET_END]
+        );
+    }
+
+    #[test]
+    fn parse_empty_response_buffer() {
+        if let Err(e) = Response::parse_bytes(&[]) {
+            assert_eq!(e, ProtocolError::Incomplete);
+        };
+    }
+
+    #[test]
+    fn parse_response_buffer_with_incorrect_start_marker() {
+        if let Err(e) = Response::parse_bytes(&[21, 0, 0, 0, 0, 0]) {
+            assert_eq!(e, ProtocolError::BadStartMarker);
+        };
+    }
+
+    #[test]
+    fn parse_response_buffer_with_unknown_command() {
+        assert_eq!(
+            Response::parse_bytes(&[PACKET_START, 0x01, 0x61, AnswerCode::StatusUpdate as u8, 1, 0x00, PACKET_END]),
+            Err(ProtocolError::UnknownCommand(0x61))
+        );
+    }
+
+    #[test]
+    fn parse_response_buffer_with_unknown_answer_code() {
+        assert_eq!(
+            Response::parse_bytes(&[PACKET_START, 0x01, Command::Power.code(), 0x99, 1, 0x00, PACKET_END]),
+            Err(ProtocolError::UnknownAnswerCode(0x99))
+        );
+    }
+
+    #[test]
+    fn parse_response_buffer_with_incorrect_end_marker() {
+        assert_eq!(
+            Response::parse_bytes(&[PACKET_START, 0x01, Command::Power.code(), AnswerCode::StatusUpdate as u8, 1, 0x00, 0x00]),
+            Err(ProtocolError::BadEndMarker)
+        );
+    }
+
+    #[test]
+    fn parse_valid_display_brightness_response() {
+        let response = Response::new(ZoneNumber::One, Command::DisplayBrightness, AnswerCode::StatusUpdate, vec![0x01]).unwrap();
+        assert_eq!(
+            Response::parse_bytes(&response.to_bytes()).unwrap(),
+            (response, 7)
+        );
+    }
+
+    #[test]
+    fn parse_buffer_with_multiple_response_packets() {
+        let r1 = Response::new(ZoneNumber::One, Command::RequestCurrentSource, AnswerCode::StatusUpdate, vec![REQUEST_QUERY]).unwrap();
+        let r2 = Response::new(ZoneNumber::One, Command::DisplayBrightness, AnswerCode::StatusUpdate, vec![REQUEST_QUERY]).unwrap();
+        let r3 = Response::new(ZoneNumber::One, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![30u8]).unwrap();
+        let mut input = r1.to_bytes();
+        input.append(&mut r2.to_bytes());
+        input.append(&mut r3.to_bytes());
+        assert_eq!(input, vec![
+            PACKET_START, ZoneNumber::One as u8, Command::RequestCurrentSource.code(), AnswerCode::StatusUpdate as u8, 1, REQUEST_QUERY, PACKET_END,
+            PACKET_START, ZoneNumber::One as u8, Command::DisplayBrightness.code(), AnswerCode::StatusUpdate as u8, 1, REQUEST_QUERY, PACKET_END,
+            PACKET_START, ZoneNumber::One as u8, Command::SetRequestVolume.code(), AnswerCode::StatusUpdate as u8, 1, 30, PACKET_END,
+        ]);
+        assert_eq!(
+            Response::parse_bytes(&input).unwrap(),
+            (r1, 7)
+        );
+    }
+
+    //  Some real response packets from an AVR850.
+
+    #[test]
+    fn create_station_name_response() {
+        let response = Response::new(ZoneNumber::One, Command::RequestDABStation, AnswerCode::StatusUpdate, "Smooth Country  ".as_bytes().to_vec()).unwrap();
+        assert_eq!(
+            response.to_bytes(),
+            [33, 1, 24, 0, 16, 83, 109, 111, 111, 116, 104, 32, 67, 111, 117, 110, 116, 114, 121, 32, 32, 13]
+        )
+    }
+
+    #[test]
+    fn parse_station_name_response() {
+        let input = [33, 1, 24, 0, 16, 83, 109, 111, 111, 116, 104, 32, 67, 111, 117, 110, 116, 114, 121, 32, 32, 13];
+        let response = Response::new(ZoneNumber::One, Command::RequestDABStation, AnswerCode::StatusUpdate, "Smooth Country  ".as_bytes().to_vec()).unwrap();
+        assert_eq!(Response::parse_bytes(&input).unwrap(), (response, 22));
+    }
+
+    #[test]
+    fn create_station_category_response() {
+        let response = Response::new(ZoneNumber::One, Command::ProgrammeTypeCategory, AnswerCode::StatusUpdate, "Country Music   ".as_bytes().to_vec()).unwrap();
+        assert_eq!(
+            response.to_bytes(),
+            [33, 1, 25, 0, 16, 67, 111, 117, 110, 116, 114, 121, 32, 77, 117, 115, 105, 99, 32, 32, 32, 13]
+        );
+    }
+
+    #[test]
+    fn parse_station_category_response() {
+        let input = [33, 1, 25, 0, 16, 67, 111, 117, 110, 116, 114, 121, 32, 77, 117, 115, 105, 99, 32, 32, 32, 13];
+        let response = Response::new(ZoneNumber::One, Command::ProgrammeTypeCategory, AnswerCode::StatusUpdate, "Country Music   ".as_bytes().to_vec()).unwrap();
+        assert_eq!(Response::parse_bytes(&input).unwrap(), (response, 22));
+    }
+
+    #[test]
+    fn parse_station_rds_dls() {
+        // String here is actually "Now on Smooth: Living In A Box with Room In Your Heart"
+        let input = [33, 1, 26, 0, 129, 12, 78, 111, 119, 32, 111, 110, 32, 83, 109, 111, 111, 116, 104, 58,
+                32, 76, 105, 118, 105, 110, 103, 32, 73, 110, 32, 65, 32, 66, 111, 120, 32, 119, 105, 116, 104, 32, 82,
+                111, 111, 109, 32, 73, 110, 32, 89, 111, 117, 114, 32, 72, 101, 97, 114, 116, 0, 0, 32, 32, 32, 32, 32,
+                32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32,
+                32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32,
+                32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 13,];
+        assert_eq!(input.len(), 135);
+        let response = Response::new(ZoneNumber::One, Command::DLSPDTInformation, AnswerCode::StatusUpdate,
+                                     vec![12, 78, 111, 119, 32, 111, 110, 32, 83, 109, 111, 111, 116, 104, 58,
+                  32, 76, 105, 118, 105, 110, 103, 32, 73, 110, 32, 65, 32, 66, 111, 120, 32, 119, 105, 116, 104, 32, 82,
+                  111, 111, 109, 32, 73, 110, 32, 89, 111, 117, 114, 32, 72, 101, 97, 114, 116, 0, 0, 32, 32, 32, 32, 32,
+                  32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32,
+                  32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32,
+                  32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32, 32,]).unwrap();
+        assert_eq!(Response::parse_bytes(&input).unwrap(), (response, 135));
+    }
+
+    #[test]
+    fn decode_volume_event() {
+        let response = Response::new(ZoneNumber::Two, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![0x14]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Ok(AmpEvent::Volume { zone: ZoneNumber::Two, level: Volume::new(20).unwrap() }));
+    }
+
+    #[test]
+    fn decode_volume_event_out_of_range() {
+        let response = Response::new(ZoneNumber::One, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![100]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Err(ProtocolError::BadValue(Command::SetRequestVolume, 100)));
+    }
+
+    #[test]
+    fn decode_event_with_wrong_data_length() {
+        let response = Response::new(ZoneNumber::One, Command::Power, AnswerCode::StatusUpdate, vec![0x01, 0x01]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Err(ProtocolError::BadDataLength(Command::Power, 2)));
+    }
+
+    #[test]
+    fn decode_event_with_unknown_enum_value() {
+        let response = Response::new(ZoneNumber::One, Command::RequestCurrentSource, AnswerCode::StatusUpdate, vec![0x07]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Err(ProtocolError::BadValue(Command::RequestCurrentSource, 0x07)));
+    }
+
+    #[test]
+    fn decode_rc5_event() {
+        let (system, code) = get_rc5command_data(RC5Command::Zone2CD);
+        let response = Response::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, AnswerCode::StatusUpdate, vec![system, code]).unwrap();
+        match AmpEvent::try_from(&response) {
+            Ok(AmpEvent::RC5Command { zone, command }) => {
+                assert_eq!(zone, ZoneNumber::Two);
+                assert_eq!(command.primary(), RC5Command::Zone2CD);
+            },
+            x => panic!("Got {:?} rather than an RC5 command event.", x),
+        }
+        let response = Response::new(ZoneNumber::One, Command::SimulateRC5IRCommand, AnswerCode::StatusUpdate, vec![0x42, 0x42]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Err(ProtocolError::UnknownRC5Code(0x42, 0x42)));
+    }
+
+    #[test]
+    fn decode_error_answer() {
+        let response = Response::new(ZoneNumber::Two, Command::RequestCurrentSource, AnswerCode::CommandInvalidAtThisTime, vec![]).unwrap();
+        assert_eq!(
+            AmpEvent::try_from(&response),
+            Err(ProtocolError::ErrorAnswer(Command::RequestCurrentSource, AnswerCode::CommandInvalidAtThisTime))
+        );
+    }
+
+    #[test]
+    fn decode_decode_mode_events() {
+        let response = Response::new(ZoneNumber::One, Command::RequestDecodeModeStatus2ch, AnswerCode::StatusUpdate, vec![0x0A]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Ok(AmpEvent::DecodeMode2ch(DecodeMode2ch::DTSNeuralX)));
+        let response = Response::new(ZoneNumber::One, Command::RequestDecodeModeStatusMCH, AnswerCode::StatusUpdate, vec![0x02]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Ok(AmpEvent::DecodeModeMCH(DecodeModeMCH::MultiChannel)));
+    }
+
+    #[test]
+    fn decode_station_name_event() {
+        let input = [33, 1, 24, 0, 16, 83, 109, 111, 111, 116, 104, 32, 67, 111, 117, 110, 116, 114, 121, 32, 32, 13];
+        let (response, _) = Response::parse_bytes(&input).unwrap();
+        assert_eq!(
+            AmpEvent::try_from(&response),
+            Ok(AmpEvent::DABStation { zone: ZoneNumber::One, name: "Smooth Country".to_string() })
+        );
+    }
+
+    #[test]
+    fn decode_dls_pdt_event() {
+        let mut data = vec![12];
+        data.extend_from_slice("Now on Smooth: Gary King".as_bytes());
+        data.extend_from_slice(&[0, 0]);
+        data.resize(129, 32);
+        let response = Response::new(ZoneNumber::One, Command::DLSPDTInformation, AnswerCode::StatusUpdate, data).unwrap();
+        assert_eq!(
+            AmpEvent::try_from(&response),
+            Ok(AmpEvent::DlsPdt { zone: ZoneNumber::One, text: "Now on Smooth: Gary King".to_string() })
+        );
+    }
+
+    #[test]
+    fn decode_incoming_audio_format_event() {
+        let response = Response::new(ZoneNumber::One, Command::RequestIncomingAudioFormat, AnswerCode::StatusUpdate, vec![0x16, 0x1C]).unwrap();
+        let event = AmpEvent::try_from(&response);
+        let channels = ChannelConfiguration::Channels { layout: ChannelLayout::StereoCentreSurroundLRSurroundBackLR, lfe: true };
+        assert_eq!(event, Ok(AmpEvent::IncomingAudioFormat { format: AudioFormat::DolbyAtmos, channels }));
+        assert_eq!(channels.to_string(), "Stereo + centre + surround L/R + surround back L/R + LFE");
+        let response = Response::new(ZoneNumber::One, Command::RequestIncomingAudioFormat, AnswerCode::StatusUpdate, vec![0x11, 0x02]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Err(ProtocolError::BadValue(Command::RequestIncomingAudioFormat, 0x11)));
+        let response = Response::new(ZoneNumber::One, Command::RequestIncomingAudioFormat, AnswerCode::StatusUpdate, vec![0x00, 0x22]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Err(ProtocolError::BadValue(Command::RequestIncomingAudioFormat, 0x22)));
+    }
+
+    #[test]
+    fn channel_configuration_covers_all_bytes() {
+        for byte in 0..=255u8 {
+            let configuration = ChannelConfiguration::decode(byte);
+            assert_eq!(configuration.is_some(), byte <= 0x21, "{:#04x}", byte);
+            if let Some(configuration) = configuration { assert_eq!(configuration.encode(), byte); }
+        }
+    }
+
+    #[test]
+    fn decode_incoming_audio_sample_rate_event() {
+        let response = Response::new(ZoneNumber::One, Command::RequestIncomingAudioSampleRate, AnswerCode::StatusUpdate, vec![0x01]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Ok(AmpEvent::IncomingAudioSampleRate(SampleRate::Hz44100)));
+        assert_eq!(SampleRate::Hz44100.to_string(), "44.1 kHz");
+        assert_eq!(SampleRate::Hz192000.to_string(), "192 kHz");
+        assert_eq!(SampleRate::Undetected.to_string(), "Undetected");
+    }
+
+    #[test]
+    fn decode_incoming_video_parameters_event() {
+        let data = vec![0x0F, 0x00, 0x08, 0x70, 24, 0x00, 0x02, 0x01];
+        let response = Response::new(ZoneNumber::One, Command::RequestIncomingVideoParameters, AnswerCode::StatusUpdate, data.clone()).unwrap();
+        let parameters = VideoParameters {
+            horizontal_resolution: 3840,
+            vertical_resolution: 2160,
+            refresh_rate: 24,
+            interlaced: false,
+            aspect_ratio: AspectRatio::Ratio16x9,
+            colourspace: Colourspace::HDR10,
+        };
+        assert_eq!(AmpEvent::try_from(&response), Ok(AmpEvent::IncomingVideoParameters(parameters)));
+        assert_eq!(parameters.encode(), data);
+        assert_eq!(parameters.to_string(), "3840×2160p 24 Hz, 16:9, HDR10");
+    }
+
+    #[test]
+    fn video_parameters_display() {
+        let parameters = VideoParameters::decode(&[0x07, 0x80, 0x04, 0x38, 50, 0x01, 0x00, 0x00]).unwrap();
+        assert_eq!(parameters.to_string(), "1920×1080i 50 Hz, SDR");
+        let parameters = VideoParameters::decode(&[0; VideoParameters::DATA_LENGTH]).unwrap();
+        assert!(!parameters.is_present());
+        assert_eq!(parameters.to_string(), "No video");
+    }
+
+    #[test]
+    fn decode_bad_video_parameters() {
+        let cc = Command::RequestIncomingVideoParameters;
+        assert_eq!(VideoParameters::decode(&[0x07, 0x80, 0x04, 0x38, 60, 0x00, 0x02]), Err(ProtocolError::BadDataLength(cc, 7)));
+        assert_eq!(VideoParameters::decode(&[0x07, 0x80, 0x04, 0x38, 60, 0x02, 0x02, 0x00]), Err(ProtocolError::BadValue(cc, 0x02)));
+        assert_eq!(VideoParameters::decode(&[0x07, 0x80, 0x04, 0x38, 60, 0x00, 0x03, 0x00]), Err(ProtocolError::BadValue(cc, 0x03)));
+        assert_eq!(VideoParameters::decode(&[0x07, 0x80, 0x04, 0x38, 60, 0x00, 0x02, 0x05]), Err(ProtocolError::BadValue(cc, 0x05)));
+    }
+
+    #[test]
+    fn video_source_of_sources() {
+        assert_eq!(Source::BD.video_source(), Some(VideoSource::BD));
+        assert_eq!(Source::GAME.video_source(), Some(VideoSource::Game));
+        assert_eq!(Source::CD.video_source(), None);
+        assert_eq!(Source::FollowZone1.video_source(), None);
+    }
+
+    #[test]
+    fn query_software_version_command_sends_component() {
+        assert_eq!(
+            AmpCommand::QuerySoftwareVersion(SoftwareComponent::Host).to_requests(),
+            Ok(vec![Request::new(ZoneNumber::One, Command::SoftwareVersion, vec![0xF1]).unwrap()])
+        );
+    }
+
+    #[test]
+    fn decode_software_version_event() {
+        let response = Response::new(ZoneNumber::One, Command::SoftwareVersion, AnswerCode::StatusUpdate, vec![0xF2, 0x01, 0x05]).unwrap();
+        let event = AmpEvent::try_from(&response);
+        assert_eq!(event, Ok(AmpEvent::SoftwareVersion(SoftwareVersion { component: SoftwareComponent::OSD, major: 1, minor: 5 })));
+        if let Ok(AmpEvent::SoftwareVersion(version)) = event { assert_eq!(version.to_string(), "OSD 1.5"); }
+        let response = Response::new(ZoneNumber::One, Command::SoftwareVersion, AnswerCode::StatusUpdate, vec![0x01, 0x01, 0x05]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Err(ProtocolError::BadValue(Command::SoftwareVersion, 0x01)));
+        let response = Response::new(ZoneNumber::One, Command::SoftwareVersion, AnswerCode::StatusUpdate, vec![0xF4, 0x02, 0x10]).unwrap();
+        let event = AmpEvent::try_from(&response);
+        assert_eq!(event, Ok(AmpEvent::SoftwareVersion(SoftwareVersion { component: SoftwareComponent::Other(0xF4), major: 2, minor: 16 })));
+        if let Ok(AmpEvent::SoftwareVersion(version)) = event { assert_eq!(version.to_string(), "Component 0xF4 2.16"); }
+    }
+
+    #[test]
+    fn set_volume_command_uses_native_request() {
+        assert_eq!(
+            AmpCommand::SetVolume(ZoneNumber::Two, Volume::new(15).unwrap()).to_requests(),
+            Ok(vec![Request::new(ZoneNumber::Two, Command::SetRequestVolume, vec![15]).unwrap()])
+        );
+        assert_eq!(Volume::new(100), Err(ProtocolError::ValueOutOfRange(100, 0, 99)));
+    }
+
+    #[test]
+    fn volume_decodes_only_valid_levels() {
+        for byte in 0..=255u8 {
+            assert_eq!(Volume::decode(byte).map(|v| v.encode()), if byte < 100 { Some(byte) } else { None }, "{}", byte);
+        }
+    }
+
+    #[test]
+    fn volume_approximate_decibels() {
+        assert_eq!(Volume::MIN.approximate_decibels(), -83);
+        assert_eq!(Volume::new(83).unwrap().approximate_decibels(), 0);
+        assert_eq!(Volume::MAX.approximate_decibels(), 16);
+    }
+
+    #[test]
+    fn volume_steps_saturate() {
+        let volume = Volume::new(95).unwrap();
+        assert_eq!(volume.saturating_up(2), Volume::new(97).unwrap());
+        assert_eq!(volume.saturating_up(10), Volume::MAX);
+        assert_eq!(volume.saturating_up(255), Volume::MAX);
+        assert_eq!(volume.saturating_down(10), Volume::new(85).unwrap());
+        assert_eq!(Volume::new(3).unwrap().saturating_down(10), Volume::MIN);
+    }
+
+    #[test]
+    fn select_source_command_uses_rc5_for_the_zone() {
+        let rc5_data = get_rc5command_data(RC5Command::CD);
+        assert_eq!(
+            AmpCommand::SelectSource(ZoneNumber::One, Source::CD).to_requests(),
+            Ok(vec![
+                Request::new(ZoneNumber::One, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1]).unwrap(),
+                Request::new(ZoneNumber::One, Command::RequestCurrentSource, vec![REQUEST_QUERY]).unwrap(),
+            ])
+        );
+        let rc5_data = get_rc5command_data(RC5Command::Zone2CD);
+        assert_eq!(
+            AmpCommand::SelectSource(ZoneNumber::Two, Source::CD).to_requests(),
+            Ok(vec![
+                Request::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1]).unwrap(),
+                Request::new(ZoneNumber::Two, Command::RequestCurrentSource, vec![REQUEST_QUERY]).unwrap(),
+            ])
+        );
+    }
+
+    #[test]
+    fn zone_1_cannot_follow_zone_1() {
+        assert_eq!(
+            AmpCommand::SelectSource(ZoneNumber::One, Source::FollowZone1).to_requests(),
+            Err(ProtocolError::SourceNotForZone(Source::FollowZone1, ZoneNumber::One))
+        );
+    }
+
+    #[test]
+    fn only_zone_2_can_follow_zone_1() {
+        assert!(Source::FollowZone1.applies_to_zone(ZoneNumber::Two));
+        assert!(!Source::FollowZone1.applies_to_zone(ZoneNumber::One));
+        assert!(Source::CD.applies_to_zone(ZoneNumber::One));
+        assert!(Source::CD.applies_to_zone(ZoneNumber::Two));
+    }
+
+    #[test]
+    fn display_brightness_is_zone_1_only() {
+        assert!(Command::DisplayBrightness.applies_to_zone(ZoneNumber::One));
+        assert!(!Command::DisplayBrightness.applies_to_zone(ZoneNumber::Two));
+        assert!(Command::SetRequestVolume.applies_to_zone(ZoneNumber::Two));
+        assert_eq!(
+            Request::new(ZoneNumber::Two, Command::DisplayBrightness, vec![REQUEST_QUERY]).unwrap().check_zone(),
+            Err(ProtocolError::CommandNotForZone(Command::DisplayBrightness, ZoneNumber::Two))
+        );
+        assert_eq!(Request::new(ZoneNumber::One, Command::DisplayBrightness, vec![REQUEST_QUERY]).unwrap().check_zone(), Ok(()));
+    }
+
+    #[test]
+    fn rc5_commands_are_checked_against_the_zone() {
+        assert_eq!(RC5Command::Zone2CD.zone(), ZoneNumber::Two);
+        assert_eq!(RC5Command::MuteOn.zone(), ZoneNumber::One);
+        let rc5_data = get_rc5command_data(RC5Command::Zone2CD);
+        assert_eq!(
+            Request::new(ZoneNumber::One, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1]).unwrap().check_zone(),
+            Err(ProtocolError::RC5CommandNotForZone(RC5Command::Zone2CD, ZoneNumber::One))
+        );
+        assert_eq!(Request::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1]).unwrap().check_zone(), Ok(()));
+        let rc5_data = get_rc5command_data(RC5Command::MuteOn);
+        assert_eq!(
+            Request::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1]).unwrap().check_zone(),
+            Err(ProtocolError::RC5CommandNotForZone(RC5Command::MuteOn, ZoneNumber::Two))
+        );
+    }
+
+    #[test]
+    fn set_power_command_queries_after_rc5() {
+        let rc5_data = get_rc5command_data(RC5Command::Zone2PowerOn);
+        assert_eq!(
+            AmpCommand::SetPower(ZoneNumber::Two, PowerState::On).to_requests(),
+            Ok(vec![
+                Request::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1]).unwrap(),
+                Request::new(ZoneNumber::Two, Command::Power, vec![REQUEST_QUERY]).unwrap(),
+            ])
+        );
+    }
+
+    #[test]
+    fn set_bass_command_encodes_sign_and_magnitude() {
+        assert_eq!(
+            AmpCommand::SetBass(DecibelOffset::new(-3).unwrap()).to_requests(),
+            Ok(vec![Request::new(ZoneNumber::One, Command::BassEqualisation, vec![0x83]).unwrap()])
+        );
+        assert_eq!(
+            AmpCommand::SetTreble(DecibelOffset::new(12).unwrap()).to_requests(),
+            Ok(vec![Request::new(ZoneNumber::One, Command::TrebleEqualisation, vec![0x0C]).unwrap()])
+        );
+        assert_eq!(DecibelOffset::new(13), Err(ProtocolError::ValueOutOfRange(13, -12, 12)));
+    }
+
+    #[test]
+    fn offset_construction_is_checked_for_all_values() {
+        for steps in i8::MIN..=i8::MAX {
+            let valid = (-12..=12).contains(&steps);
+            assert_eq!(DecibelOffset::new(steps).is_ok(), valid, "{}", steps);
+            assert_eq!(HalfDecibelOffset::new(steps).is_ok(), valid, "{}", steps);
+        }
+    }
+
+    #[test]
+    fn offset_decodes_exactly_the_sign_and_magnitude_bytes() {
+        for byte in 0..=255u8 {
+            let expected = match byte {
+                0x00..=0x0C => Some(byte as i8),
+                0x81..=0x8C => Some(-((byte & 0x7F) as i8)),
+                _ => None,
+            };
+            assert_eq!(DecibelOffset::decode(byte).map(|o| o.decibels()), expected, "{:#04x}", byte);
+            assert_eq!(HalfDecibelOffset::decode(byte).map(|o| o.half_decibels()), expected, "{:#04x}", byte);
+            if let Some(offset) = DecibelOffset::decode(byte) { assert_eq!(offset.encode(), byte); }
+            if let Some(offset) = HalfDecibelOffset::decode(byte) { assert_eq!(offset.encode(), byte); }
+        }
+    }
+
+    #[test]
+    fn offset_display_shows_units() {
+        assert_eq!(DecibelOffset::new(0).unwrap().to_string(), "0 dB");
+        assert_eq!(DecibelOffset::new(4).unwrap().to_string(), "+4 dB");
+        assert_eq!(DecibelOffset::new(-12).unwrap().to_string(), "-12 dB");
+        assert_eq!(HalfDecibelOffset::new(0).unwrap().to_string(), "0.0 dB");
+        assert_eq!(HalfDecibelOffset::new(3).unwrap().to_string(), "+1.5 dB");
+        assert_eq!(HalfDecibelOffset::new(-12).unwrap().to_string(), "-6.0 dB");
+    }
+
+    #[test]
+    fn lipsync_delay_covers_all_bytes_and_milliseconds() {
+        for byte in 0..=255u8 {
+            let delay = LipsyncDelay::decode(byte);
+            assert_eq!(delay.is_some(), byte <= 0x32, "{:#04x}", byte);
+            if let Some(delay) = delay {
+                assert_eq!(delay.encode(), byte);
+                assert_eq!(delay.milliseconds(), byte as u16 * 5);
+                assert_eq!(LipsyncDelay::from_milliseconds(delay.milliseconds()), Ok(delay));
+            }
+        }
+        assert_eq!(LipsyncDelay::from_milliseconds(255), Err(ProtocolError::ValueOutOfRange(255, 0, 250)));
+        assert_eq!(LipsyncDelay::from_milliseconds(12), Err(ProtocolError::ValueOutOfRange(12, 0, 250)));
+        assert_eq!(LipsyncDelay::new(0x32).unwrap().to_string(), "250 ms");
+    }
+
+    #[test]
+    fn offset_responses_decode_to_events() {
+        let treble = Response::new(ZoneNumber::One, Command::TrebleEqualisation, AnswerCode::StatusUpdate, vec![0x85]).unwrap();
+        assert_eq!(AmpEvent::try_from(&treble), Ok(AmpEvent::TrebleEqualisation(DecibelOffset::new(-5).unwrap())));
+        let trim = Response::new(ZoneNumber::One, Command::SubwooferTrim, AnswerCode::StatusUpdate, vec![0x03]).unwrap();
+        assert_eq!(AmpEvent::try_from(&trim), Ok(AmpEvent::SubwooferTrim(HalfDecibelOffset::new(3).unwrap())));
+        let lipsync = Response::new(ZoneNumber::One, Command::LipsyncDelay, AnswerCode::StatusUpdate, vec![0x0A]).unwrap();
+        assert_eq!(AmpEvent::try_from(&lipsync), Ok(AmpEvent::LipsyncDelay(LipsyncDelay::from_milliseconds(50).unwrap())));
+        let balance = Response::new(ZoneNumber::One, Command::Balance, AnswerCode::StatusUpdate, vec![0x80]).unwrap();
+        assert_eq!(AmpEvent::try_from(&balance), Err(ProtocolError::BadValue(Command::Balance, 0x80)));
+    }
+
+    #[test]
+    fn setting_responses_decode_to_typed_events() {
+        let event = |cc: Command, data: Vec<u8>| AmpEvent::try_from(&Response::new(ZoneNumber::One, cc, AnswerCode::StatusUpdate, data).unwrap());
+        assert_eq!(event(Command::RoomEqualisation, vec![0x02]), Ok(AmpEvent::RoomEqualisation(RoomEqualisation::EQ2)));
+        assert_eq!(event(Command::IMAXEnhanced, vec![0x02]), Ok(AmpEvent::IMAXEnhanced(IMAXEnhancedMode::Auto)));
+        assert_eq!(event(Command::SetRequestVideoOutputSwitching, vec![0x04]), Ok(AmpEvent::VideoOutputSwitching(VideoOutput::HDMIOut1And2)));
+        assert_eq!(event(Command::DolbyProLogicIIDimension, vec![0x82]), Ok(AmpEvent::DolbyProLogicIIDimension(Dimension::new(-2).unwrap())));
+        assert_eq!(event(Command::DolbyProLogicIIDimension, vec![0x04]), Err(ProtocolError::BadValue(Command::DolbyProLogicIIDimension, 0x04)));
+        assert_eq!(event(Command::DolbyProLogicIICentreWidth, vec![0x07]), Ok(AmpEvent::DolbyProLogicIICentreWidth(CentreWidth::new(7).unwrap())));
+        assert_eq!(event(Command::DolbyProLogicIICentreWidth, vec![0x08]), Err(ProtocolError::BadValue(Command::DolbyProLogicIICentreWidth, 0x08)));
+        assert_eq!(event(Command::DolbyVolumeCalibrationOffset, vec![0x8F]), Ok(AmpEvent::DolbyVolumeCalibrationOffset(CalibrationOffset::new(-15).unwrap())));
+        assert_eq!(event(Command::SetRequestMosquitoNR, vec![0x03]), Ok(AmpEvent::MosquitoNoiseReduction(FilterLevel::High)));
+        assert_eq!(event(Command::SetRequestContrast, vec![0x8A]), Ok(AmpEvent::PictureContrast(PictureAdjustment::new(-10).unwrap())));
+        assert_eq!(event(Command::SetRequestOutputFrameRate, vec![0x01]), Ok(AmpEvent::OutputFrameRate(OutputFrameRate::Hz50)));
+        assert_eq!(Dimension::new(-3).unwrap().to_string(), "-3");
+        assert_eq!(CalibrationOffset::new(4).unwrap().to_string(), "+4 dB");
+    }
+
+    #[test]
+    fn network_responses_decode_to_typed_events() {
+        let response = Response::new(ZoneNumber::One, Command::NetworkPlaybackStatus, AnswerCode::StatusUpdate, vec![0x03]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Ok(AmpEvent::NetworkPlaybackStatus { zone: ZoneNumber::One, status: NetworkPlaybackStatus::Paused }));
+        let response = Response::new(ZoneNumber::One, Command::RequestPresetDetails, AnswerCode::StatusUpdate, vec![0x05, 0x01, 98, 50]).unwrap();
+        let station = PresetStation::FMFrequency { megahertz: 98, ten_kilohertz: 50 };
+        assert_eq!(station.to_string(), "FM 98.50 MHz");
+        assert_eq!(AmpEvent::try_from(&response), Ok(AmpEvent::PresetDetails { zone: ZoneNumber::One, preset: 5, station }));
+        let mut data = vec![0x0C, 0x03];
+        data.extend(b"Smooth Country  ");
+        let response = Response::new(ZoneNumber::Two, Command::RequestPresetDetails, AnswerCode::StatusUpdate, data).unwrap();
+        assert_eq!(
+            AmpEvent::try_from(&response),
+            Ok(AmpEvent::PresetDetails { zone: ZoneNumber::Two, preset: 12, station: PresetStation::DABStation("Smooth Country".to_string()) }));
+        let response = Response::new(ZoneNumber::One, Command::RequestPresetDetails, AnswerCode::StatusUpdate, vec![0x05, 0x07, 98]).unwrap();
+        assert_eq!(AmpEvent::try_from(&response), Err(ProtocolError::BadValue(Command::RequestPresetDetails, 0x07)));
+    }
+
+    #[test]
+    fn avr600_video_input_type_uses_imax_code() {
+        let request = Request::new(ZoneNumber::One, Command::SetRequestVideoInputType, vec![REQUEST_QUERY]).unwrap();
+        let bytes = request.to_bytes();
+        assert_eq!(bytes, vec![PACKET_START, 0x01, 0x0C, 0x01, REQUEST_QUERY, PACKET_END]);
+        assert_eq!(Request::parse_bytes_in_dialect(&bytes, Dialect::AVR600), Ok((request, bytes.len())));
+        assert_eq!(
+            Request::parse_bytes(&bytes),
+            Ok((Request::new(ZoneNumber::One, Command::IMAXEnhanced, vec![REQUEST_QUERY]).unwrap(), bytes.len()))
+        );
+    }
+
+    #[test]
+    fn dialects_reject_commands_of_other_dialect() {
+        assert_eq!(Dialect::AVR850.command(0x46), Err(ProtocolError::UnknownCommand(0x46)));
+        assert_eq!(Dialect::AVR600.command(0x46), Ok(Command::SetRequestBrightness));
+        assert_eq!(Dialect::AVR600.command(0x25), Err(ProtocolError::UnknownCommand(0x25)));
+        assert_eq!(Dialect::AVR850.command(0x8C), Err(ProtocolError::UnknownCommand(0x8C)));
+        assert_eq!(Dialect::AVR600.command(0x8C), Err(ProtocolError::UnknownCommand(0x8C)));
+    }
+
+    #[test]
+    fn avr600_command_names() {
+        assert_eq!(Dialect::AVR600.command_name(Command::RequestDABStation), "RequestDABSiriusStation");
+        assert_eq!(Dialect::AVR850.command_name(Command::RequestDABStation), "RequestDABStation");
+        assert_eq!(Dialect::AVR600.parse_command_name("RequestRDSDLSInformation"), Some(Command::DLSPDTInformation));
+        assert_eq!(Dialect::AVR600.parse_command_name("DLSPDTInformation"), Some(Command::DLSPDTInformation));
+        assert_eq!(Dialect::AVR850.parse_command_name("RequestRDSDLSInformation"), None);
+        assert_eq!(Dialect::AVR850.parse_command_name("Heartbeat"), Some(Command::Heartbeat));
+        assert_eq!(Dialect::AVR600.parse_command_name("Heartbeat"), None);
+        assert_eq!(Dialect::AVR850.parse_command_name("SetRequestContrast"), None);
+        assert_eq!(Dialect::AVR600.parse_command_name("SetRequestContrast"), Some(Command::SetRequestContrast));
+        assert_eq!(Dialect::AVR850.parse_command_name("NoSuchCommand"), None);
+    }
+
+    #[test]
+    fn dialect_code_tables_agree_both_ways() {
+        for dialect in [Dialect::AVR850, Dialect::AVR600].iter() {
+            for (cc, code) in dialect.code_tables().iter().flat_map(|table| table.iter()) {
+                assert_eq!(dialect.command(*code), Ok(*cc));
+                assert_eq!(dialect.code(*cc), Some(*code));
+            }
+        }
+        assert_eq!(Dialect::AVR600.code(Command::SetRequestVideoInputType), Some(0x0C));
+        assert_eq!(Dialect::AVR850.code(Command::SetRequestVideoInputType), None);
+        assert_eq!(Command::SetRequestVideoInputType.code(), 0x0C);
+        assert_eq!(Command::IMAXEnhanced.code(), 0x0C);
+    }
+
+    #[test]
+    fn decoder_uses_dialect() {
+        let bytes = Response::new(ZoneNumber::One, Command::SetRequestContrast, AnswerCode::StatusUpdate, vec![0x05]).unwrap().to_bytes();
+        let mut decoder = PacketDecoder::<Response>::with_dialect(Dialect::AVR600);
+        decoder.push(&bytes);
+        assert_eq!(
+            decoder.next_frame(),
+            Some(Frame::Packet(Response::new(ZoneNumber::One, Command::SetRequestContrast, AnswerCode::StatusUpdate, vec![0x05]).unwrap()))
+        );
+        decoder.set_dialect(Dialect::AVR850);
+        decoder.push(&bytes);
+        assert_eq!(decoder.next_frame(), Some(Frame::Skipped { count: bytes.len(), error: ProtocolError::UnknownCommand(0x47) }));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn request_json_round_trip() {
+        let request = Request::new(ZoneNumber::Two, Command::SetRequestVolume, vec![20]).unwrap();
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"zone":"Two","cc":"SetRequestVolume","data":[20]}"#);
+        assert_eq!(serde_json::from_str::<Request>(&json).unwrap(), request);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn response_toml_round_trip() {
+        let response = Response::new(ZoneNumber::One, Command::Power, AnswerCode::StatusUpdate, vec![PowerState::On as u8]).unwrap();
+        let text = toml::to_string(&response).unwrap();
+        assert_eq!(text, "zone = \"One\"\ncc = \"Power\"\nac = \"StatusUpdate\"\ndata = [1]\n");
+        assert_eq!(toml::from_str::<Response>(&text).unwrap(), response);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialising_rejects_data_longer_than_255() {
+        let data = format!("[{}]", vec!["0"; 256].join(","));
+        let json = format!(r#"{{"zone":"One","cc":"Power","data":{}}}"#, data);
+        assert!(serde_json::from_str::<Request>(&json).unwrap_err().to_string().contains("more than 255"));
+        let json = format!(r#"{{"zone":"One","cc":"Power","ac":"StatusUpdate","data":{}}}"#, data);
+        assert!(serde_json::from_str::<Response>(&json).unwrap_err().to_string().contains("more than 255"));
+        let json = format!(r#"{{"zone":"One","cc":"Power","data":[{}]}}"#, vec!["0"; 255].join(","));
+        assert_eq!(serde_json::from_str::<Request>(&json).unwrap().data.len(), 255);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn value_types_serialise_as_names() {
+        assert_eq!(serde_json::to_string(&Source::TUNERDAB).unwrap(), r#""TUNERDAB""#);
+        assert_eq!(serde_json::to_string(&RC5Command::Zone2CD).unwrap(), r#""Zone2CD""#);
+        assert_eq!(serde_json::from_str::<Brightness>(r#""Level1""#).unwrap(), Brightness::Level1);
+        assert_eq!(serde_json::from_str::<MuteState>(r#""Muted""#).unwrap(), MuteState::Muted);
+        assert_eq!(serde_json::from_str::<AnswerCode>(r#""ZoneInvalid""#).unwrap(), AnswerCode::ZoneInvalid);
+    }
+
+    #[test]
+    fn amx_request_is_correct() {
+        assert_eq!(create_amx_request(), b"AMX\r".to_vec());
+    }
+
+    #[test]
+    fn parse_amx_reply() {
+        let amx = "AMXB<Device-SDKClass=Receiver><Device-Make=ARCAM><Device-Model=AVR850><Device-Revision=2.0.0>";
+        let expected = DeviceInfo {
+            sdk_class: "Receiver".to_string(),
+            make: "ARCAM".to_string(),
+            model: "AVR850".to_string(),
+            revision: "2.0.0".to_string(),
+        };
+        assert_eq!(amx.parse::<DeviceInfo>(), Ok(expected.clone()));
+        assert_eq!(format!("{}\r", amx).parse::<DeviceInfo>(), Ok(expected));
+    }
+
+    #[test]
+    fn parse_malformed_amx_reply() {
+        assert_eq!("AMX".parse::<DeviceInfo>(), Err(ProtocolError::BadAmxMessage));
+        assert_eq!("AMXB<Device-Make=ARCAM><Device-Model=AVR850>".parse::<DeviceInfo>(), Err(ProtocolError::BadAmxMessage));
+        assert_eq!("AMXB<Device-SDKClass=Receiver><Device-Make=ARCAM><Device-Model=AVR850><Device-Revision=2.0.0".parse::<DeviceInfo>(), Err(ProtocolError::BadAmxMessage));
+    }
+
+    #[test]
+    fn rc5_data_correctly_accessed() {
+        assert_eq!(RC5_DATA[RC5Command::Nine as usize], (RC5Command::Nine, (0x10, 0x09)));
+        assert_eq!(get_rc5command_data(RC5Command::Nine), (0x10, 0x09));
+    }
+
+    #[test]
+    fn rc5_data_in_variant_order() {
+        for (index, (rc5command, _)) in RC5_DATA.iter().enumerate() {
+            assert_eq!(*rc5command as usize, index);
+        }
+    }
+
+    #[test]
+    fn rc5command_round_trip() {
+        for (rc5command, data) in RC5_DATA.iter() {
+            assert!(RC5Aliases::try_from(*data).unwrap().contains(*rc5command));
+        }
+    }
+
+    #[test]
+    fn rc5_shared_codes_give_all_aliases() {
+        assert_eq!(RC5Aliases::try_from((0x10, 0x2b)).unwrap().commands(), &[RC5Command::HOME, RC5Command::Yellow]);
+        assert_eq!(
+            RC5Aliases::try_from(get_rc5command_data(RC5Command::Blue)).unwrap().commands(),
+            &[RC5Command::CycleBetweenVFDInformationPanels, RC5Command::Blue]
+        );
+        assert_eq!(RC5Aliases::try_from((0x10, 0x7b)).unwrap().commands(), &[RC5Command::PowerOn]);
+    }
+
+    #[test]
+    fn rc5_unknown_code_is_an_error() {
+        assert_eq!(RC5Aliases::try_from((0x10, 0x7f)), Err(ProtocolError::UnknownRC5Code(0x10, 0x7f)));
+        assert_eq!(RC5Aliases::try_from(&[0x10u8][..]), Err(ProtocolError::BadDataLength(Command::SimulateRC5IRCommand, 1)));
+    }
+
+    #[test]
+    fn debug_of_request_with_unknown_rc5_code() {
+        let request = Request::new(ZoneNumber::One, Command::SimulateRC5IRCommand, vec![0x10, 0x7f]).unwrap();
+        assert!(!format!("{:?}", request).contains("rc5command"));
+    }
+
+    #[test]
+    fn decoder_extracts_packets_split_across_chunks() {
+        let r1 = Response::new(ZoneNumber::One, Command::DisplayBrightness, AnswerCode::StatusUpdate, vec![0x01]).unwrap();
+        let r2 = Response::new(ZoneNumber::Two, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![0x14]).unwrap();
+        let mut input = r1.to_bytes();
+        input.append(&mut r2.to_bytes());
+        let mut decoder = PacketDecoder::<Response>::new();
+        decoder.push(&input[..3]);
+        assert_eq!(decoder.next_frame(), None);
+        decoder.push(&input[3..9]);
+        assert_eq!(decoder.next_frame(), Some(Frame::Packet(r1)));
+        assert_eq!(decoder.next_frame(), None);
+        decoder.push(&input[9..]);
+        assert_eq!(decoder.next_frame(), Some(Frame::Packet(r2)));
+        assert_eq!(decoder.next_frame(), None);
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn decoder_extracts_interleaved_amx_messages() {
+        let amx = "AMXB<Device-SDKClass=Receiver><Device-Make=ARCAM><Device-Model=AVR850><Device-Revision=2.0.0>";
+        let r1 = Response::new(ZoneNumber::One, Command::Power, AnswerCode::StatusUpdate, vec![0x01]).unwrap();
+        let mut input = r1.to_bytes();
+        input.extend_from_slice(amx.as_bytes());
+        input.push(PACKET_END);
+        input.append(&mut r1.to_bytes());
+        let mut decoder = PacketDecoder::<Response>::new();
+        decoder.push(&input[..10]);
+        decoder.push(&input[10..]);
+        assert_eq!(
+            decoder.collect::<Vec<Frame<Response>>>(),
+            vec![Frame::Packet(r1.clone()), Frame::Amx(amx.to_string()), Frame::Packet(r1)]
+        );
+    }
+
+    #[test]
+    fn decoder_waits_for_partial_amx_start() {
+        let mut decoder = PacketDecoder::<Request>::new();
+        decoder.push(b"AM");
+        assert_eq!(decoder.next_frame(), None);
+        decoder.push(b"X\r");
+        assert_eq!(decoder.next_frame(), Some(Frame::Amx("AMX".to_string())));
+    }
+
+    #[test]
+    fn decoder_resynchronises_after_garbage() {
+        let r1 = Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![0x14]).unwrap();
+        let mut input = vec![0x00, 0x41, 0x42, 0x0d];
+        input.append(&mut r1.to_bytes());
+        let mut decoder = PacketDecoder::<Request>::new();
+        decoder.push(&input);
+        assert_eq!(decoder.next_frame(), Some(Frame::Skipped { count: 4, error: ProtocolError::BadStartMarker }));
+        assert_eq!(decoder.next_frame(), Some(Frame::Packet(r1)));
+        assert_eq!(decoder.next_frame(), None);
+    }
+
+    #[test]
+    fn decoder_resynchronises_after_bad_end_marker() {
+        let r1 = Response::new(ZoneNumber::One, Command::Power, AnswerCode::StatusUpdate, vec![0x01]).unwrap();
+        let mut input = vec![PACKET_START, 0x01, 0x00, 0x00, 0x01, 0x01, 0x00];
+        input.append(&mut r1.to_bytes());
+        let mut decoder = PacketDecoder::<Response>::new();
+        decoder.push(&input);
+        assert_eq!(decoder.next_frame(), Some(Frame::Skipped { count: 7, error: ProtocolError::BadEndMarker }));
+        assert_eq!(decoder.next_frame(), Some(Frame::Packet(r1)));
+    }
+
+    #[test]
+    fn decoder_skips_well_framed_packet_with_unknown_command() {
+        let r1 = Response::new(ZoneNumber::One, Command::Power, AnswerCode::StatusUpdate, vec![0x01]).unwrap();
+        let mut input = vec![PACKET_START, 0x01, 0x61, 0x00, 0x02, 0x01, 0x02, PACKET_END];
+        input.append(&mut r1.to_bytes());
+        let mut decoder = PacketDecoder::<Response>::new();
+        decoder.push(&input);
+        assert_eq!(decoder.next_frame(), Some(Frame::Skipped { count: 8, error: ProtocolError::UnknownCommand(0x61) }));
+        assert_eq!(decoder.next_frame(), Some(Frame::Packet(r1)));
+    }
+
+    #[test]
+    fn decoder_handles_many_small_chunks() {
+        let r1 = Response::new(ZoneNumber::One, Command::RequestDABStation, AnswerCode::StatusUpdate, "Smooth Country  ".as_bytes().to_vec()).unwrap();
+        let input = r1.to_bytes();
+        let mut decoder = PacketDecoder::<Response>::new();
+        let mut frames = vec![];
+        for _ in 0..100 {
+            for b in input.iter() {
+                decoder.push(&[*b]);
+                frames.extend(&mut decoder);
+            }
+        }
+        assert_eq!(frames.len(), 100);
+        assert!(frames.iter().all(|f| *f == Frame::Packet(r1.clone())));
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn request_notation_examples() {
+        let volume = Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![20]).unwrap();
+        assert_eq!(volume.to_string(), "Z1 SetRequestVolume 20");
+        assert_eq!("Z1 SetRequestVolume 20".parse::<Request>(), Ok(volume));
+        let (system, command) = get_rc5command_data(RC5Command::Zone2CD);
+        let cd = Request::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, vec![system, command]).unwrap();
+        assert_eq!(cd.to_string(), "Z2 RC5 Zone2CD");
+        assert_eq!("Z2 RC5 Zone2CD".parse::<Request>(), Ok(cd));
+        let query = Request::new(ZoneNumber::One, Command::Power, vec![REQUEST_QUERY]).unwrap();
+        assert_eq!(query.to_string(), "Z1 Power?");
+        assert_eq!("Z1 Power?".parse::<Request>(), Ok(query));
+    }
+
+    #[test]
+    fn response_notation_examples() {
+        let power = Response::new(ZoneNumber::One, Command::Power, AnswerCode::StatusUpdate, vec![0x01]).unwrap();
+        assert_eq!(power.to_string(), "Z1 Power -> StatusUpdate On");
+        assert_eq!("Z1 Power? -> StatusUpdate On".parse::<Response>(), Ok(power.clone()));
+        assert_eq!("Z1 Power -> StatusUpdate On".parse::<Response>(), Ok(power));
+        let error = Response::new(ZoneNumber::Two, Command::Power, AnswerCode::ZoneInvalid, vec![]).unwrap();
+        assert_eq!(error.to_string(), "Z2 Power -> ZoneInvalid");
+        assert_eq!("Z2 Power -> ZoneInvalid".parse::<Response>(), Ok(error));
+    }
+
+    #[test]
+    fn notation_round_trips() {
+        let requests = vec![
+            Request::new(ZoneNumber::One, Command::SimulateRC5IRCommand, vec![0x10, 0x00]).unwrap(),
+            Request::new(ZoneNumber::One, Command::SimulateRC5IRCommand, vec![0x10, 0x7f]).unwrap(),
+            Request::new(ZoneNumber::One, Command::SetRequestInputName, b"My \"CD\" \\ \x01".to_vec()).unwrap(),
+            Request::new(ZoneNumber::One, Command::SetRequestBrightness, vec![5]).unwrap(),
+            Request::new(ZoneNumber::Two, Command::Heartbeat, vec![]).unwrap(),
+        ];
+        for request in requests {
+            assert_eq!(request.to_string().parse::<Request>(), Ok(request));
+        }
+        let responses = vec![
+            Response::new(ZoneNumber::One, Command::RequestDABStation, AnswerCode::StatusUpdate, b"Smooth Country  \x00".to_vec()).unwrap(),
+            Response::new(ZoneNumber::Two, Command::RequestCurrentSource, AnswerCode::StatusUpdate, vec![0x00]).unwrap(),
+            Response::new(ZoneNumber::One, Command::RequestCurrentSource, AnswerCode::StatusUpdate, vec![0x7f]).unwrap(),
+            Response::new(ZoneNumber::One, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![REQUEST_QUERY]).unwrap(),
+            Response::new(ZoneNumber::One, Command::SoftwareVersion, AnswerCode::StatusUpdate, vec![0x01, 0x02, 0x03]).unwrap(),
+        ];
+        for response in responses {
+            assert_eq!(response.to_string().parse::<Response>(), Ok(response));
+        }
+    }
+
+    #[test]
+    fn notation_accepts_avr600_command_names() {
+        let request: Request = "Z1 RequestDABSiriusStation?".parse().unwrap();
+        assert_eq!(request, Request::new(ZoneNumber::One, Command::RequestDABStation, vec![REQUEST_QUERY]).unwrap());
+    }
+
+    #[test]
+    fn notation_rejects_malformed_text() {
+        for text in &["", "Z1", "Z3 Power?", "Power?", "Z1 Volume 20", "Z1 SetRequestVolume 256", "Z1 Power? 1", "Z1 RC5 NoSuchButton", "Z1 SetRequestInputName \"CD"] {
+            assert_eq!(text.parse::<Request>(), Err(ProtocolError::BadNotation), "{}", text);
+        }
+        for text in &["Z1 Power", "Z1 Power StatusUpdate On", "Z1 Power -> Fine"] {
+            assert_eq!(text.parse::<Response>(), Err(ProtocolError::BadNotation), "{}", text);
+        }
+    }
+
+    #[test]
+    fn pending_requests_tie_error_answer_to_request() {
+        let mut pending = PendingRequests::new();
+        let requests = AmpCommand::SelectSource(ZoneNumber::Two, Source::CD).to_requests().unwrap();
+        for request in requests.iter() { pending.push(request.clone()); }
+        let rc5 = &requests[0];
+        let response = Response::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, AnswerCode::CommandInvalidAtThisTime, rc5.data.clone()).unwrap();
+        let error = pending.answer(&response).unwrap_err();
+        assert_eq!(error.request.as_ref(), Some(rc5));
+        assert_eq!(error.answer, ErrorAnswerCode::CommandInvalidAtThisTime);
+        assert_eq!(error.action(), "change source");
+        assert_eq!(error.to_string(), "Zone 2 cannot change source at this time.");
+        let response = Response::new(ZoneNumber::Two, Command::RequestCurrentSource, AnswerCode::StatusUpdate, vec![Source::FollowZone1 as u8]).unwrap();
+        assert_eq!(pending.answer(&response), Ok(Some(requests[1].clone())));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn pending_requests_ignore_unprompted_status_updates() {
+        let mut pending = PendingRequests::new();
+        pending.push(Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![REQUEST_QUERY]).unwrap());
+        let response = Response::new(ZoneNumber::Two, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![20]).unwrap();
+        assert_eq!(pending.answer(&response), Ok(None));
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn pending_requests_answer_oldest_request_first() {
+        let mut pending = PendingRequests::new();
+        let first = Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![30]).unwrap();
+        let second = Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![31]).unwrap();
+        pending.push(first.clone());
+        pending.push(second.clone());
+        let response = Response::new(ZoneNumber::One, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![30]).unwrap();
+        assert_eq!(pending.answer(&response), Ok(Some(first)));
+        let response = Response::new(ZoneNumber::One, Command::SetRequestVolume, AnswerCode::ParameterNotRecognized, vec![]).unwrap();
+        let error = pending.answer(&response).unwrap_err();
+        assert_eq!(error.request, Some(second));
+        assert_eq!(error.to_string(), "Zone 1 cannot change volume, the amplifier does not recognise the value.");
+    }
+
+    #[test]
+    fn pending_requests_forget_oldest_when_full() {
+        let mut pending = PendingRequests::new();
+        for _ in 0..=MAX_PENDING_REQUESTS {
+            pending.push(Request::new(ZoneNumber::One, Command::Power, vec![REQUEST_QUERY]).unwrap());
+        }
+        assert_eq!(pending.len(), MAX_PENDING_REQUESTS);
+        pending.clear();
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn pending_requests_forget_unanswered_request() {
+        let mut pending = PendingRequests::new();
+        let query = Request::new(ZoneNumber::One, Command::Power, vec![REQUEST_QUERY]).unwrap();
+        let other = Request::new(ZoneNumber::Two, Command::Power, vec![REQUEST_QUERY]).unwrap();
+        pending.push(query.clone());
+        pending.push(other.clone());
+        assert!(pending.forget(&query));
+        assert!(!pending.forget(&query));
+        assert_eq!(pending.len(), 1);
+        let response = Response::new(ZoneNumber::Two, Command::Power, AnswerCode::StatusUpdate, vec![1]).unwrap();
+        assert_eq!(pending.answer(&response), Ok(Some(other)));
+    }
+
+    #[test]
+    fn only_status_updates_are_not_errors() {
+        assert_eq!(AnswerCode::StatusUpdate.error(), None);
+        for code in [0x82, 0x83, 0x84, 0x85, 0x86].iter() {
+            let answer_code: AnswerCode = FromPrimitive::from_u8(*code).unwrap();
+            let error = answer_code.error().unwrap();
+            assert_eq!(error as u8, *code);
+            assert_eq!(AnswerCode::from(error), answer_code);
+        }
+    }
+
+    #[test]
+    fn answer_error_without_request() {
+        let response = Response::new(ZoneNumber::One, Command::RequestDABStation, AnswerCode::ZoneInvalid, vec![]).unwrap();
+        let error = PendingRequests::new().answer(&response).unwrap_err();
+        assert_eq!(error.request, None);
+        assert_eq!(error.to_string(), "Zone 1 cannot carry out RequestDABStation, the command is not valid for the zone.");
+    }
+
+    #[test]
+    fn request_actions() {
+        let power_off = AmpCommand::SetPower(ZoneNumber::Two, PowerState::Standby).to_requests().unwrap();
+        assert_eq!(request_action(&power_off[0]), "go to standby");
+        assert_eq!(request_action(&power_off[1]), "report power state");
+        assert!(power_off[1].is_query());
+        let mute = AmpCommand::SetMute(ZoneNumber::One, MuteState::Muted).to_requests().unwrap();
+        assert_eq!(request_action(&mute[0]), "mute");
+        assert!(!mute[0].is_query());
+        let brightness = AmpCommand::SetBrightness(Brightness::Level1).to_requests().unwrap();
+        assert_eq!(request_action(&brightness[0]), "change display brightness");
+        let version = AmpCommand::QuerySoftwareVersion(SoftwareComponent::DSP).to_requests().unwrap();
+        assert_eq!(request_action(&version[0]), "report software version");
+        let treble = AmpCommand::SetTreble(DecibelOffset::new(2).unwrap()).to_requests().unwrap();
+        assert_eq!(request_action(&treble[0]), "change treble");
+    }
+}
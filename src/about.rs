@@ -17,7 +17,8 @@
  *  along with this program. If not, see <http://www.gnu.org/licenses/>.
  */
 
-//! This module provides the "about" dialogue for ArcamClient.
+//! This module provides the "about" dialogue for ArcamClient, and the "about this amplifier"
+//! dialogue for the amplifier it is connected to.
 
 use std::cell::Cell;
 use std::sync::Mutex;
@@ -30,8 +31,11 @@ use gtk::prelude::*;
 use gdk_pixbuf::PixbufLoader;
 use gdk_pixbuf::PixbufLoaderExt;
 
+use arcam_protocol::{DeviceInfo, SoftwareVersion};
+
 lazy_static! {
     static ref ABOUT: Mutex<Cell<bool>> = Mutex::new(Cell::new(false));
+    static ref ABOUT_AMPLIFIER: Mutex<Cell<bool>> = Mutex::new(Cell::new(false));
 }
 
 /// Create the [AboutDialog](https://gtk-rs.org/docs/gtk/struct.AboutDialog.html) instance
@@ -75,3 +79,72 @@ pub fn present(parent: Option<&gtk::ApplicationWindow>) {
         }
     }
 }
+
+/// The text describing the amplifier: the AMX identity and the software versions.
+fn amplifier_description(device: Option<&DeviceInfo>, versions: &[SoftwareVersion]) -> String {
+    let mut lines = match device {
+        Some(device) => vec![
+            format!("Make: {}", device.make),
+            format!("Model: {}", device.model),
+            format!("Revision: {}", device.revision),
+        ],
+        None => vec!["No identification received from the amplifier.".to_string()],
+    };
+    lines.extend(versions.iter().map(|v| format!("{} version: {}.{}", v.component, v.major, v.minor)));
+    lines.join("\n")
+}
+
+/// Present the about this amplifier dialog in a non-modal way, but only if one is not
+/// already displaying.
+pub fn present_amplifier(parent: Option<&gtk::ApplicationWindow>, device: Option<&DeviceInfo>, versions: &[SoftwareVersion]) {
+    if let Ok(active) = ABOUT_AMPLIFIER.lock() {
+        if ! active.get() {
+            let dialog = gtk::MessageDialog::new(
+                parent,
+                gtk::DialogFlags::DESTROY_WITH_PARENT,
+                gtk::MessageType::Info,
+                gtk::ButtonsType::Close,
+                "About this Amplifier",
+            );
+            dialog.set_property_secondary_text(Some(&amplifier_description(device, versions)));
+            dialog.connect_response(move |d, _| {
+                if let Ok(active) = ABOUT_AMPLIFIER.lock() {
+                    unsafe { d.destroy(); }
+                    active.set(false);
+                }
+            });
+            dialog.show();
+            active.set(true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arcam_protocol::SoftwareComponent;
+
+    #[test]
+    fn amplifier_description_combines_identity_and_versions() {
+        let device = DeviceInfo {
+            sdk_class: "Receiver".to_string(),
+            make: "ARCAM".to_string(),
+            model: "AVR850".to_string(),
+            revision: "2.0.0".to_string(),
+        };
+        let versions = [
+            SoftwareVersion { component: SoftwareComponent::RS232Protocol, major: 4, minor: 1 },
+            SoftwareVersion { component: SoftwareComponent::Host, major: 1, minor: 12 },
+        ];
+        assert_eq!(
+            amplifier_description(Some(&device), &versions),
+            "Make: ARCAM\nModel: AVR850\nRevision: 2.0.0\nRS232 protocol version: 4.1\nHost version: 1.12"
+        );
+    }
+
+    #[test]
+    fn amplifier_description_without_identity() {
+        assert_eq!(amplifier_description(None, &[]), "No identification received from the amplifier.");
+    }
+}
@@ -28,7 +28,10 @@
 //! this process is a server of mock AVR850 processes.
 //!
 //! A real AVR850 only listens on port 50000.  This simulation/mock can be started listening on
-//! any port in the range [50001, 65535]. If all processes started by the integration tests used
+//! any port in the range [50001, 65535]. The port number can be followed by the model of
+//! amplifier to simulate, for example AVR600, in which case the mock uses the
+//! [Dialect](../arcam_protocol/enum.Dialect.html) of the protocol for that model and
+//! identifies itself as that model. If all processes started by the integration tests used
 //! the same port then there is the possibility that a process is started whilst another is
 //! running or the port was not yet ready for reuse. In both cases the later process would fail
 //! to start, and so the integration test would fail due to lack of connection to a mock
@@ -79,6 +82,7 @@
 
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::env::args;
 use std::rc::Rc;
 use std::time::SystemTime;
@@ -95,25 +99,62 @@ use futures::prelude::*;
 
 use num_traits::FromPrimitive;
 
-use arcamclient::arcam_protocol::{
-    AnswerCode, Brightness, Command, MuteState, PowerState, RC5Command, Request, Response, Source, VideoSource, ZoneNumber,
-    PACKET_START, REQUEST_QUERY,
+use arcam_protocol::amp_model::Model;
+use arcam_protocol::{
+    AnswerCode, AspectRatio, AudioFormat, Brightness, ChannelConfiguration, ChannelLayout, Colourspace, Command, FilterLevel, Frame,
+    MuteState, OutputFrameRate, PacketDecoder, PictureAdjustment, PictureMode, PowerState, ProtocolError, RC5Aliases, RC5Command,
+    Request, Response, SampleRate, SoftwareComponent, Source, VideoInputType, VideoParameters, VideoSource, Volume, ZoneNumber,
+    REQUEST_QUERY,
 };
 
 /// State for an AVR zone.
 #[derive(Debug)]
 struct ZoneState {
     power: Cell<PowerState>,
-    volume: Cell<u8>, // Must be in range [0..100)
+    volume: Cell<Volume>,
     mute: Cell<MuteState>,
     source: Cell<Source>,
 }
 
-/// The state of a mock AVR.  An AVR850 comprises two zones, an AVR600 comprised 3 zones.
+/// State of the video processor of an AVR600, an AVR850 does not have one.
+#[derive(Debug)]
+struct VideoProcessorState {
+    input_type: Cell<VideoInputType>,
+    brightness: Cell<PictureAdjustment>,
+    contrast: Cell<PictureAdjustment>,
+    colour: Cell<PictureAdjustment>,
+    picture_mode: Cell<PictureMode>,
+    edge_enhancement: Cell<FilterLevel>,
+    mosquito_noise_reduction: Cell<FilterLevel>,
+    noise_reduction: Cell<FilterLevel>,
+    block_noise_reduction: Cell<FilterLevel>,
+    output_frame_rate: Cell<OutputFrameRate>,
+}
+
+impl Default for VideoProcessorState {
+    fn default() -> Self {
+        Self {
+            input_type: Cell::new(VideoInputType::HDMI),
+            brightness: Cell::new(PictureAdjustment::default()),
+            contrast: Cell::new(PictureAdjustment::default()),
+            colour: Cell::new(PictureAdjustment::default()),
+            picture_mode: Cell::new(PictureMode::Video),
+            edge_enhancement: Cell::new(FilterLevel::Off),
+            mosquito_noise_reduction: Cell::new(FilterLevel::Off),
+            noise_reduction: Cell::new(FilterLevel::Low),
+            block_noise_reduction: Cell::new(FilterLevel::Off),
+            output_frame_rate: Cell::new(OutputFrameRate::Auto),
+        }
+    }
+}
+
+/// The state of a mock AVR.  An AVR850 comprises two zones, an AVR600 three zones but only
+/// zones 1 and 2 are supported.
 #[derive(Debug)]
 struct AmpState {
     zones: HashMap<ZoneNumber, ZoneState>,
     brightness: Cell<Brightness>,
+    video_processor: VideoProcessorState,
 }
 
 impl Default for AmpState {
@@ -121,12 +162,13 @@ impl Default for AmpState {
         let mut amp_state = Self {
             zones: HashMap::new(),
             brightness: Cell::new(Brightness::Level2),
+            video_processor: VideoProcessorState::default(),
         };
         amp_state.zones.insert(
             ZoneNumber::One,
             ZoneState{
                 power: Cell::new(PowerState::On),
-                volume: Cell::new(30),
+                volume: Cell::new(Volume::new(30).unwrap()),
                 mute: Cell::new(MuteState::NotMuted),
                 source: Cell::new(Source::CD),
             });
@@ -134,7 +176,7 @@ impl Default for AmpState {
             ZoneNumber::Two,
             ZoneState{
                 power: Cell::new(PowerState::Standby),
-                volume: Cell::new(20),
+                volume: Cell::new(Volume::new(20).unwrap()),
                 mute: Cell::new(MuteState::NotMuted),
                 source: Cell::new(Source::FollowZone1),
             });
@@ -142,9 +184,27 @@ impl Default for AmpState {
     }
 }
 
+/// Return a response to a request that queries or sets a single byte value of the state of the
+/// mock amp, setting the value if the request is not a query.
+fn query_or_set_value<T: Copy>(request: &Request, value: &Cell<T>, encode: fn(T) -> u8, decode: fn(u8) -> Option<T>) -> Result<Response, String> {
+    assert_eq!(request.data.len(), 1);
+    if request.data[0] != REQUEST_QUERY {
+        match decode(request.data[0]) {
+            Some(v) => value.set(v),
+            None => return Err(format!("Incorrect {:?} command {:?}.", request.cc, request.data[0])),
+        }
+    }
+    Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, vec![encode(value.get())]).unwrap())
+}
+
 /// Return a response to a given request updating the state of the mock amp as needed.
 fn create_command_response(request: &Request, amp_state_ptr: Rc<RefCell<AmpState>>, sender: Option<futures::channel::mpsc::Sender<Vec<u8>>>) -> Result<Response, String>{
     let amp_state = amp_state_ptr.borrow_mut();
+    match request.check_zone() {
+        Err(ProtocolError::CommandNotForZone(_, _)) | Err(ProtocolError::RC5CommandNotForZone(_, _)) =>
+            return Ok(Response::new(request.zone, request.cc, AnswerCode::ZoneInvalid, request.data.clone()).unwrap()),
+        _ => {},
+    }
     match request.cc {
         Command::Power => {
             assert_eq!(request.data.len(), 1);
@@ -165,10 +225,10 @@ fn create_command_response(request: &Request, amp_state_ptr: Rc<RefCell<AmpState
         Command::SetRequestVolume => {
             assert_eq!(request.data.len(), 1);
             if request.data[0] == REQUEST_QUERY {
-                Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, vec![amp_state.zones[&request.zone].volume.get()]).unwrap())
-            } else if request.data[0] < 100 {
-                amp_state.zones[&request.zone].volume.set(request.data[0]);
-                Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, vec![amp_state.zones[&request.zone].volume.get()]).unwrap())
+                Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, vec![amp_state.zones[&request.zone].volume.get().encode()]).unwrap())
+            } else if let Some(volume) = Volume::decode(request.data[0]) {
+                amp_state.zones[&request.zone].volume.set(volume);
+                Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, vec![volume.encode()]).unwrap())
             } else {
                 Err(format!("Failed to deal with SetRequestVolume command {:?}.", request.cc))
             }
@@ -190,20 +250,83 @@ fn create_command_response(request: &Request, amp_state_ptr: Rc<RefCell<AmpState
                 Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, vec![is_mute]).unwrap())
             }
         },
+        Command::RequestIncomingAudioFormat | Command::RequestIncomingAudioSampleRate => {
+            assert_eq!(request.data.len(), 1);
+            if request.data[0] != REQUEST_QUERY {
+                return Err(format!("Incorrect {:?} command {:?}.", request.cc, request.data[0]));
+            }
+            let source = match amp_state.zones[&request.zone].source.get() {
+                Source::FollowZone1 => amp_state.zones[&ZoneNumber::One].source.get(),
+                source => source,
+            };
+            let (format, channels, sample_rate) = match source {
+                Source::BD => (
+                    AudioFormat::DolbyAtmos,
+                    ChannelConfiguration::Channels { layout: ChannelLayout::StereoCentreSurroundLRSurroundBackLR, lfe: true },
+                    SampleRate::Hz48000,
+                ),
+                Source::CD => (AudioFormat::PCM, ChannelConfiguration::Channels { layout: ChannelLayout::StereoOnly, lfe: false }, SampleRate::Hz44100),
+                _ => (AudioFormat::PCM, ChannelConfiguration::Channels { layout: ChannelLayout::StereoOnly, lfe: false }, SampleRate::Hz48000),
+            };
+            let data = if request.cc == Command::RequestIncomingAudioFormat { vec![format as u8, channels.encode()] } else { vec![sample_rate as u8] };
+            Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, data).unwrap())
+        },
+        Command::RequestIncomingVideoParameters => {
+            assert_eq!(request.data.len(), 1);
+            if request.data[0] != REQUEST_QUERY {
+                return Err(format!("Incorrect {:?} command {:?}.", request.cc, request.data[0]));
+            }
+            let source = match amp_state.zones[&request.zone].source.get() {
+                Source::FollowZone1 => amp_state.zones[&ZoneNumber::One].source.get(),
+                source => source,
+            };
+            // Sources without HDMI video report no video stream.
+            let parameters = match source {
+                Source::BD => VideoParameters {
+                    horizontal_resolution: 3840,
+                    vertical_resolution: 2160,
+                    refresh_rate: 24,
+                    interlaced: false,
+                    aspect_ratio: AspectRatio::Ratio16x9,
+                    colourspace: Colourspace::HDR10,
+                },
+                source if source.video_source().is_some() => VideoParameters {
+                    horizontal_resolution: 1920,
+                    vertical_resolution: 1080,
+                    refresh_rate: 50,
+                    interlaced: true,
+                    aspect_ratio: AspectRatio::Ratio16x9,
+                    colourspace: Colourspace::Normal,
+                },
+                _ => VideoParameters {
+                    horizontal_resolution: 0,
+                    vertical_resolution: 0,
+                    refresh_rate: 0,
+                    interlaced: false,
+                    aspect_ratio: AspectRatio::Undefined,
+                    colourspace: Colourspace::Normal,
+                },
+            };
+            Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, parameters.encode()).unwrap())
+        },
+        Command::SoftwareVersion => {
+            assert_eq!(request.data.len(), 1);
+            let (major, minor) = match SoftwareComponent::decode(request.data[0]) {
+                Some(SoftwareComponent::RS232Protocol) => (4, 1),
+                Some(SoftwareComponent::Host) => (1, 12),
+                Some(SoftwareComponent::OSD) => (2, 4),
+                Some(SoftwareComponent::DSP) => (3, 0),
+                // Components the mock does not have are refused with an error answer.
+                Some(SoftwareComponent::Other(_)) | None =>
+                    return Ok(Response::new(request.zone, request.cc, AnswerCode::ParameterNotRecognized, request.data.clone()).unwrap()),
+            };
+            Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, vec![request.data[0], major, minor]).unwrap())
+        },
         Command::VideoSelection => {
             // TODO is the source the same as the video source in the amp?
             assert_eq!(request.data.len(), 1);
             if request.data[0] == REQUEST_QUERY {
-                let video_source = match amp_state.zones[&request.zone].source.get() {
-                    Source::BD => VideoSource::BD,
-                    Source::SAT =>VideoSource::SAT,
-                    Source::AV =>VideoSource::AV,
-                    Source::PVR =>VideoSource::PVR,
-                    Source::VCR =>VideoSource::VCR,
-                    Source::GAME =>VideoSource::Game,
-                    Source::STB =>VideoSource::STB,
-                    _ => panic!("Illegal video source."),
-                };
+                let video_source = amp_state.zones[&request.zone].source.get().video_source().expect("Illegal video source.");
                 Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, vec![video_source as u8]).unwrap())
             } else {
                 let source = match FromPrimitive::from_u8(request.data[0]).unwrap() {
@@ -220,8 +343,10 @@ fn create_command_response(request: &Request, amp_state_ptr: Rc<RefCell<AmpState
             }
         },
         Command::SimulateRC5IRCommand => {
-            assert_eq!(request.data.len(), 2);
-            let rc5command: RC5Command = (request.data[0], request.data[1]).into();
+            let rc5command = match RC5Aliases::try_from(&request.data[..]) {
+                Ok(aliases) => aliases.primary(),
+                Err(e) => return Err(e.to_string()),
+            };
             match rc5command {
                 RC5Command::DisplayOff => amp_state.brightness.set(Brightness::Off),
                 RC5Command::DisplayL1 => amp_state.brightness.set(Brightness::Level1),
@@ -234,8 +359,12 @@ fn create_command_response(request: &Request, amp_state_ptr: Rc<RefCell<AmpState
                     assert_eq!(request.zone, ZoneNumber::One);
                     amp_state.zones[&request.zone].mute.set(MuteState::NotMuted);
                 },
-                RC5Command::Radio => {
-                    amp_state.zones[&request.zone].source.set(Source::TUNER);
+                RC5Command::Radio | RC5Command::DAB | RC5Command::Zone2FM | RC5Command::Zone2DAB => {
+                    let source = match rc5command {
+                        RC5Command::Radio | RC5Command::Zone2FM => Source::TUNER,
+                        _ => Source::TUNERDAB,
+                    };
+                    amp_state.zones[&request.zone].source.set(source);
                     if sender.is_some() {
                         glib::MainContext::default().spawn_local(
                             send_tuner_data_and_start_dls_pdt_sending(amp_state_ptr.clone(), request.zone, sender.unwrap().clone()));
@@ -281,10 +410,94 @@ fn create_command_response(request: &Request, amp_state_ptr: Rc<RefCell<AmpState
                     assert_eq!(request.zone, ZoneNumber::Two);
                     amp_state.zones[&request.zone].mute.set(MuteState::NotMuted);
                 },
+                RC5Command::Zone2CD => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::CD)
+                },
+                RC5Command::Zone2BD => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::BD)
+                },
+                RC5Command::Zone2AV => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::AV)
+                },
+                RC5Command::Zone2Sat => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::SAT)
+                },
+                RC5Command::Zone2PVR => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::PVR)
+                },
+                RC5Command::Zone2VCR => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::VCR)
+                },
+                RC5Command::Zone2Aux => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::AUX)
+                },
+                RC5Command::Zone2NET => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::NET)
+                },
+                RC5Command::Zone2USB => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::USB)
+                },
+                RC5Command::Zone2STB => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::STB)
+                },
+                RC5Command::Zone2Game => {
+                    assert_eq!(request.zone, ZoneNumber::Two);
+                    amp_state.zones[&request.zone].source.set(Source::GAME)
+                },
                 _ => return Err("Not implemented.".to_string()),
             };
             Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, request.data.clone()).unwrap())
         },
+        Command::SetRequestVideoInputType => {
+            query_or_set_value(request, &amp_state.video_processor.input_type, |v| v as u8, FromPrimitive::from_u8)
+        },
+        Command::SetRequestBrightness => {
+            query_or_set_value(request, &amp_state.video_processor.brightness, |v| v.encode(), PictureAdjustment::decode)
+        },
+        Command::SetRequestContrast => {
+            query_or_set_value(request, &amp_state.video_processor.contrast, |v| v.encode(), PictureAdjustment::decode)
+        },
+        Command::SetRequestColour => {
+            query_or_set_value(request, &amp_state.video_processor.colour, |v| v.encode(), PictureAdjustment::decode)
+        },
+        Command::SetRequestPictureMode => {
+            query_or_set_value(request, &amp_state.video_processor.picture_mode, |v| v as u8, FromPrimitive::from_u8)
+        },
+        Command::SetRequestEdgeEnhancement => {
+            query_or_set_value(request, &amp_state.video_processor.edge_enhancement, |v| v as u8, FromPrimitive::from_u8)
+        },
+        Command::SetRequestMosquitoNR => {
+            query_or_set_value(request, &amp_state.video_processor.mosquito_noise_reduction, |v| v as u8, FromPrimitive::from_u8)
+        },
+        Command::SetRequestNoiseReduction => {
+            query_or_set_value(request, &amp_state.video_processor.noise_reduction, |v| v as u8, FromPrimitive::from_u8)
+        },
+        Command::SetRequestBlockNoiseReduction => {
+            query_or_set_value(request, &amp_state.video_processor.block_noise_reduction, |v| v as u8, FromPrimitive::from_u8)
+        },
+        Command::SetRequestOutputFrameRate => {
+            query_or_set_value(request, &amp_state.video_processor.output_frame_rate, |v| v as u8, FromPrimitive::from_u8)
+        },
+        Command::Heartbeat => {
+            assert_eq!(request.data.len(), 1);
+            if request.data[0] != REQUEST_QUERY {
+                Err(format!("Incorrect Heartbeat command {:?}.", request.data[0]))
+            } else {
+                Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, vec![0x00]).unwrap())
+            }
+        },
+        // The connection is closed once the response is sent, see process_connection.
+        Command::Reboot => Ok(Response::new(request.zone, request.cc, AnswerCode::StatusUpdate, request.data.clone()).unwrap()),
         x => Err(format!("Failed to deal with command {:?}.", x)),
     }
 }
@@ -313,20 +526,22 @@ async fn send_tuner_data_and_start_dls_pdt_sending(amp_state_ptr: Rc<RefCell<Amp
                 // indicates a real AVR850 returns 129 characters.The manual states that the
                 // string is padded with spaces to fill the 128 characters. A real AVR850 seems
                 // to null terminate the string, with two nulls if possible, and then pad the
-                // 129 characters with spaces.
+                // 129 characters with spaces. The first byte is a control byte rather than text,
+                // captured packets all had 12 or 25.
                 let mut dls_pdt_buffer = [' ' as u8; 129];
+                dls_pdt_buffer[0] = 12;
                 // Quite weird that elapsed doesn't return zero!
                 let dsl_pdt_data = format!("This DLS/PDT information sent after {:?}", SystemTime::now().elapsed().unwrap());
-                assert!(dsl_pdt_data.len() <= 128);
-                let mut i = 0;
+                assert!(dsl_pdt_data.len() <= 127);
+                let mut i = 1;
                 for c in dsl_pdt_data.bytes() {
                     dls_pdt_buffer[i] = c;
                     i += 1;
                 }
-                assert_eq!(i, dsl_pdt_data.len());
+                assert_eq!(i, dsl_pdt_data.len() + 1);
                 dls_pdt_buffer[i] = 0;
                 // An AVR850 appears to put two null bytes in the buffer if it can.
-                if dsl_pdt_data.len() < 128 {
+                if dsl_pdt_data.len() < 127 {
                     i += 1;
                     dls_pdt_buffer[i] = 0;
                 }
@@ -358,7 +573,7 @@ fn create_string_for_socketaddress(address: &gio::SocketAddress) -> String {
 /// AVR850 might.
 ///
 /// Each connection has its own [AmpState](struct.AmpState.html) so appears as a distinct mock AVR850.
-async fn process_connection(connection: SocketConnection, amp_state_ptr: Rc<RefCell<AmpState>>) {
+async fn process_connection(connection: SocketConnection, amp_state_ptr: Rc<RefCell<AmpState>>, model: Model) {
     let remote_address = connection.get_remote_address().unwrap();
     debug!("process_connection: connection from {}", &create_string_for_socketaddress(&remote_address));
     let (mut reader, mut writer) = connection.split();
@@ -371,45 +586,49 @@ async fn process_connection(connection: SocketConnection, amp_state_ptr: Rc<RefC
             }
         }
     });
+    let mut decoder = PacketDecoder::<Request>::with_dialect(model.dialect());
+    // A real AVR850 drops the connection when it reboots.
+    let mut rebooting = false;
     loop {
-        let mut buffer = [0u8; 1024];  // Must be bigger than the byte size of the maximum number of simultaneous packets receivable.
+        let mut buffer = [0u8; 1024];
         match reader.read(&mut buffer).await {
             Ok(read_count) => {
                 if read_count == 0 {
                     debug!("process_connection: zero length read, assuming connection from {} closed.", &create_string_for_socketaddress(&remote_address));
                     break;
-                } else {
-                    let mut data = &buffer[..read_count];
-                    if data[0] == PACKET_START {
-                        // Process an Arcam packet. There may be more than one packet in this TCP message.
-                        // TODO What happens if there is an AMX\r within the TCP message?
-                        while data.len() > 0 {
-                            match Request::parse_bytes(data) {
-                                Ok((request, count)) => {
-                                    data = &data[count..];
-                                    match create_command_response(&request, amp_state_ptr.clone(), Some(tx_send_queue.clone())) {
-                                        Ok(response) => {
-                                            debug!("process_connection: sending the response {:?}", &response);
-                                            match tx_send_queue.try_send(response.to_bytes()) {
-                                                Ok(_) => debug!("process_connection: put response on the queue."),
-                                                Err(e) => debug!("process_connection: failed to put response on the queue – {}", e),
-                                            };
-                                        },
-                                        Err(e) => debug!("process_connection: failed to process a request – {}", e),
+                }
+                // There may be more than one packet, or part of a packet, in this TCP message.
+                decoder.push(&buffer[..read_count]);
+                for frame in &mut decoder {
+                    match frame {
+                        Frame::Packet(request) => {
+                            rebooting |= request.cc == Command::Reboot;
+                            match create_command_response(&request, amp_state_ptr.clone(), Some(tx_send_queue.clone())) {
+                                Ok(response) => {
+                                    debug!("process_connection: sending the response {:?}", &response);
+                                    match tx_send_queue.try_send(response.to_bytes()) {
+                                        Ok(_) => debug!("process_connection: put response on the queue."),
+                                        Err(e) => debug!("process_connection: failed to put response on the queue – {}", e),
                                     };
                                 },
-                                Err(e) => debug!("process_connection: failed to parse {:?} as a request – {}", &data, e),
+                                Err(e) => debug!("process_connection: failed to process a request – {}", e),
                             };
-                        }
-                    } else {
-                        debug!("process_connection: received a non-packet message – {:?}", &data);
-                        match tx_send_queue.try_send(
-                            "AMXB<Device-SDKClass=Receiver><Device-Make=ARCAM><Device-Model=AVR850><Device-Revision=2.0.0>\r".as_bytes().to_vec()) {
-                            Ok(_) => debug!("process_connection: put AMX response on the queue."),
-                            Err(e) => debug!("process_connection: failed to put AMX response on the queue – {}", e),
-                        }
+                        },
+                        // A real AVR850 responds to anything that is not an Arcam packet with an AMX response.
+                        Frame::Amx(_) | Frame::Skipped { .. } => {
+                            debug!("process_connection: received a non-packet message – {:?}", &frame);
+                            match tx_send_queue.try_send(
+                                format!("AMXB<Device-SDKClass=Receiver><Device-Make=ARCAM><Device-Model={}><Device-Revision=2.0.0>\r", model).as_bytes().to_vec()) {
+                                Ok(_) => debug!("process_connection: put AMX response on the queue."),
+                                Err(e) => debug!("process_connection: failed to put AMX response on the queue – {}", e),
+                            }
+                        },
                     }
                 }
+                if rebooting {
+                    debug!("process_connection: rebooting, closing connection from {}.", &create_string_for_socketaddress(&remote_address));
+                    break;
+                }
             },
             Err(e) => debug!("process_connection: read failed – {}", e),
         };
@@ -426,7 +645,7 @@ async fn process_connection(connection: SocketConnection, amp_state_ptr: Rc<RefC
 /// A real AVR850 only allows a single connection at a time, dropping the prior connection in
 /// favour of the new connection request. This is not modelled in this mock. Instead each
 /// connection looks like a connection to a different mock AVR850.
-async fn run_connection_listener(port_number: u16) {
+async fn run_connection_listener(port_number: u16, model: Model) {
     let server = SocketListener::new();
     let address = gio::InetSocketAddress::new(&gio::InetAddress::from_string("127.0.0.1").unwrap(), port_number);
     server.add_address(&address, gio::SocketType::Stream, gio::SocketProtocol::Tcp, None::<&glib::Object>).expect("Failed to bind to address.");
@@ -444,7 +663,7 @@ async fn run_connection_listener(port_number: u16) {
                     Err(_) => "error".to_string(),
                 };
                 debug!("run_connection_listener: got a connection on {} from {}", &local_address, &remote_address);
-                glib::MainContext::default().spawn_local(process_connection(s_c, Rc::new(RefCell::new(AmpState::default()))));
+                glib::MainContext::default().spawn_local(process_connection(s_c, Rc::new(RefCell::new(AmpState::default())), model));
             },
             Err(e) => debug!("run_connection_listener: got an errorful connection request – {}", e),
         }
@@ -467,12 +686,13 @@ fn main() {
     env_logger::init();
     let args: Vec<String> = args().collect();
     debug!("main: args are {:?}", args);
-    let default_port_number = 50000;
+    let model = if args.len() > 2 { args[2].parse::<Model>().unwrap_or_default() } else { Model::default() };
+    let default_port_number = model.default_port();
     let port_number = if args.len() > 1 { args[1].parse::<u16>().unwrap_or(default_port_number) } else { default_port_number };
-    debug!("main: starting event loop.");
+    debug!("main: starting event loop for a mock {}.", model);
     let context = glib::MainContext::default();
     context.push_thread_default();
-    context.block_on(run_connection_listener(port_number));
+    context.block_on(run_connection_listener(port_number, model));
     context.pop_thread_default();
     debug!("main: event loop terminated.");
 }
@@ -485,8 +705,9 @@ mod tests {
 
     use super::{AmpState, create_command_response};
 
-    use arcamclient::arcam_protocol::{
-        AnswerCode, Brightness, Command, MuteState, PowerState, RC5Command, Request, Response, Source, ZoneNumber,
+    use arcam_protocol::{
+        AnswerCode, Brightness, Command, FilterLevel, MuteState, OutputFrameRate, PictureAdjustment, PictureMode, PowerState, RC5Command,
+        Request, Response, SoftwareComponent, Source, VideoInputType, ZoneNumber,
         REQUEST_QUERY,
         get_rc5command_data,
     };
@@ -556,26 +777,65 @@ mod tests {
         assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::One].power.get(), PowerState::Standby);
     }
 
+    #[test]
+    fn get_incoming_audio_format_for_cd() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::One, Command::RequestIncomingAudioFormat, vec![REQUEST_QUERY]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::One, Command::RequestIncomingAudioFormat, AnswerCode::StatusUpdate, vec![0x00, 0x02]).unwrap());
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::Two, Command::RequestIncomingAudioSampleRate, vec![REQUEST_QUERY]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::Two, Command::RequestIncomingAudioSampleRate, AnswerCode::StatusUpdate, vec![0x01]).unwrap());
+    }
+
+    #[test]
+    fn get_incoming_video_parameters() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::One, Command::RequestIncomingVideoParameters, vec![REQUEST_QUERY]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::One, Command::RequestIncomingVideoParameters, AnswerCode::StatusUpdate, vec![0, 0, 0, 0, 0, 0, 0, 0]).unwrap());
+        amp_state_ptr.borrow().zones[&ZoneNumber::One].source.set(Source::BD);
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::Two, Command::RequestIncomingVideoParameters, vec![REQUEST_QUERY]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::Two, Command::RequestIncomingVideoParameters, AnswerCode::StatusUpdate, vec![0x0F, 0x00, 0x08, 0x70, 24, 0x00, 0x02, 0x01]).unwrap());
+    }
+
+    #[test]
+    fn get_software_version() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::One, Command::SoftwareVersion, vec![SoftwareComponent::Host.encode()]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::One, Command::SoftwareVersion, AnswerCode::StatusUpdate, vec![0xF1, 1, 12]).unwrap());
+    }
+
+    #[test]
+    fn get_software_version_of_unknown_component_is_refused() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::One, Command::SoftwareVersion, vec![0xF7]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::One, Command::SoftwareVersion, AnswerCode::ParameterNotRecognized, vec![0xF7]).unwrap());
+    }
+
     #[test]
     fn get_zone_1_volume() {
         let volume = 30u8;
         let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
-        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::One].volume.get(), volume);
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::One].volume.get().level(), volume);
         assert_eq!(
             create_command_response(&Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![REQUEST_QUERY]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
             Response::new(ZoneNumber::One, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![volume]).unwrap());
-        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::One].volume.get(), volume);
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::One].volume.get().level(), volume);
     }
 
     #[test]
     fn set_zone_1_volume() {
         let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
-        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::One].volume.get(), 30);
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::One].volume.get().level(), 30);
         assert_eq!(
             create_command_response(&Request::new(ZoneNumber::One, Command::SetRequestVolume, vec![0x0f]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
             Response::new(ZoneNumber::One, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![0x0f]).unwrap()
         );
-        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::One].volume.get(), 15);
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::One].volume.get().level(), 15);
     }
 
     #[test]
@@ -679,22 +939,22 @@ mod tests {
     fn get_zone_2_volume() {
         let volume = 20u8;
         let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
-        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].volume.get(), volume);
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].volume.get().level(), volume);
         assert_eq!(
             create_command_response(&Request::new(ZoneNumber::Two, Command::SetRequestVolume, vec![REQUEST_QUERY]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
             Response::new(ZoneNumber::Two, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![volume]).unwrap());
-        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].volume.get(), volume);
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].volume.get().level(), volume);
     }
 
     #[test]
     fn set_zone_2_volume() {
         let volume = 15u8;
         let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
-        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].volume.get(), 20);
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].volume.get().level(), 20);
         assert_eq!(
             create_command_response(&Request::new(ZoneNumber::Two, Command::SetRequestVolume, vec![volume]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
             Response::new(ZoneNumber::Two, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![volume]).unwrap());
-        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].volume.get(), volume);
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].volume.get().level(), volume);
     }
 
     #[test]
@@ -750,15 +1010,96 @@ mod tests {
     }
 
     #[test]
-    fn set_zone_2_source_using_rc5() {
+    fn set_zone_2_source_using_zone_1_rc5_is_invalid() {
         let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
         assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].source.get(), Source::FollowZone1);
         let rc5_data= get_rc5command_data(RC5Command::BD);
         let data = vec![rc5_data.0, rc5_data.1];
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, data.clone()).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, AnswerCode::ZoneInvalid, data).unwrap());
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].source.get(), Source::FollowZone1);
+    }
+
+    #[test]
+    fn reboot_is_acknowledged() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::One, Command::Reboot, b"REBOOT".to_vec()).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::One, Command::Reboot, AnswerCode::StatusUpdate, b"REBOOT".to_vec()).unwrap());
+    }
+
+    #[test]
+    fn heartbeat_is_answered() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::One, Command::Heartbeat, vec![REQUEST_QUERY]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::One, Command::Heartbeat, AnswerCode::StatusUpdate, vec![0x00]).unwrap());
+    }
+
+    #[test]
+    fn display_brightness_is_invalid_for_zone_2() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::Two, Command::DisplayBrightness, vec![REQUEST_QUERY]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::Two, Command::DisplayBrightness, AnswerCode::ZoneInvalid, vec![REQUEST_QUERY]).unwrap());
+    }
+
+    #[test]
+    fn set_zone_2_source_using_zone_2_rc5() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].source.get(), Source::FollowZone1);
+        let rc5_data= get_rc5command_data(RC5Command::Zone2CD);
+        let data = vec![rc5_data.0, rc5_data.1];
         assert_eq!(
             create_command_response(&Request::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, data.clone()).unwrap(), amp_state_ptr.clone(), None).unwrap(),
             Response::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, AnswerCode::StatusUpdate, data).unwrap());
-        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].source.get(), Source::BD);
+        assert_eq!(amp_state_ptr.borrow().zones[&ZoneNumber::Two].source.get(), Source::CD);
+    }
+
+    #[test]
+    fn get_video_processor_settings() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        for (cc, value) in [
+            (Command::SetRequestVideoInputType, VideoInputType::HDMI as u8),
+            (Command::SetRequestBrightness, PictureAdjustment::default().encode()),
+            (Command::SetRequestContrast, PictureAdjustment::default().encode()),
+            (Command::SetRequestColour, PictureAdjustment::default().encode()),
+            (Command::SetRequestPictureMode, PictureMode::Video as u8),
+            (Command::SetRequestEdgeEnhancement, FilterLevel::Off as u8),
+            (Command::SetRequestMosquitoNR, FilterLevel::Off as u8),
+            (Command::SetRequestNoiseReduction, FilterLevel::Low as u8),
+            (Command::SetRequestBlockNoiseReduction, FilterLevel::Off as u8),
+            (Command::SetRequestOutputFrameRate, OutputFrameRate::Auto as u8),
+        ].iter() {
+            assert_eq!(
+                create_command_response(&Request::new(ZoneNumber::One, *cc, vec![REQUEST_QUERY]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+                Response::new(ZoneNumber::One, *cc, AnswerCode::StatusUpdate, vec![*value]).unwrap());
+        }
+    }
+
+    #[test]
+    fn set_video_processor_settings() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        let contrast = PictureAdjustment::new(-10).unwrap();
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::One, Command::SetRequestContrast, vec![contrast.encode()]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::One, Command::SetRequestContrast, AnswerCode::StatusUpdate, vec![contrast.encode()]).unwrap());
+        assert_eq!(amp_state_ptr.borrow().video_processor.contrast.get(), contrast);
+        assert_eq!(
+            create_command_response(&Request::new(ZoneNumber::One, Command::SetRequestOutputFrameRate, vec![OutputFrameRate::Hz50 as u8]).unwrap(), amp_state_ptr.clone(), None).unwrap(),
+            Response::new(ZoneNumber::One, Command::SetRequestOutputFrameRate, AnswerCode::StatusUpdate, vec![OutputFrameRate::Hz50 as u8]).unwrap());
+        assert_eq!(amp_state_ptr.borrow().video_processor.output_frame_rate.get(), OutputFrameRate::Hz50);
+    }
+
+    #[test]
+    fn set_video_processor_setting_error() {
+        let amp_state_ptr = Rc::new(RefCell::new(AmpState::default()));
+        match create_command_response(&Request::new(ZoneNumber::One, Command::SetRequestMosquitoNR, vec![0x07]).unwrap(), amp_state_ptr.clone(), None) {
+            Ok(_) => assert!(false),
+            Err(e) => assert_eq!(e, "Incorrect SetRequestMosquitoNR command 7."),
+        }
+        assert_eq!(amp_state_ptr.borrow().video_processor.mosquito_noise_reduction.get(), FilterLevel::Off);
     }
 
 }
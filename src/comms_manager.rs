@@ -23,13 +23,30 @@
 //! Everything here is asynchronous communication with the amplifier. Communication into here
 //! and out of here is using channels. The channel for sending bytes from the amplifier to other
 //! code is provided via a call to the [connect_to_amp](fn.connect_to_amp.html) which returns
-//! the channel for sending bytes to the amplifier.
+//! a [Connection](struct.Connection.html) handle providing the channels for sending bytes to the
+//! amplifier.
+//!
+//! Bytes for the amplifier are queued and sent in priority order, user actions ahead of polling,
+//! with a minimum gap between sends: a real amplifier drops requests that arrive too quickly.
+//!
+//! A connection can have a [Heartbeat](struct.Heartbeat.html), bytes sent whenever nothing has
+//! been heard from the amplifier for a while, so that a connection that has silently died, for
+//! example because the amplifier lost power, is noticed and reported as lost.
+//!
+//! The state of the connection is reported as [ConnectionEvent](enum.ConnectionEvent.html)s
+//! sent down a channel provided in the call to [connect_to_amp](fn.connect_to_amp.html).
 //!
 //! There is no knowledge of the Arcam protocol here, everything is just byte sequences. The
 //! module [functionality](../functionality/index.html) has the functions that transform Arcam
-//! [Requests](../arcam_protocol/struct.Request.html) into byte sequences that can be sent by by
+//! [Requests](../../arcam_protocol/struct.Request.html) into byte sequences that can be sent by by
 //! code here and the functions that parse byte sequences into Arcam
-//! [Response](../arcam_protocol/struct.Response.html)s.
+//! [Response](../../arcam_protocol/struct.Response.html)s.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use gio;
 use gio::prelude::*;
@@ -37,6 +54,7 @@ use glib;
 //use glib::prelude::*;
 
 use futures;
+use futures::future::{self, AbortHandle, Abortable, Either, Fuse, FutureExt};
 use futures::AsyncReadExt;
 use futures::AsyncWriteExt;
 use futures::StreamExt;
@@ -45,15 +63,326 @@ use log::debug;
 
 use gio_futures::{SocketClient, SocketConnection};
 
+/// A handle on a connection to an amplifier.
+///
+/// The handle owns the task writing to the socket and the task listening to the socket. The
+/// connection is closed by [close](struct.Connection.html#method.close), or by dropping the
+/// handle: any pending writes are flushed, the socket is shut down, and the listener is
+/// cancelled.
+///
+/// Bytes are sent to the amplifier at the [Priority](enum.Priority.html) of the channel they
+/// are sent down, with at least the minimum gap between sends.
+#[derive(Debug)]
+pub struct Connection {
+    to_amp: futures::channel::mpsc::Sender<Vec<u8>>,
+    polling_to_amp: futures::channel::mpsc::Sender<Vec<u8>>,
+    minimum_gap: Rc<Cell<Duration>>,
+    heartbeat: Rc<RefCell<Option<Heartbeat>>>,
+    heartbeat_changed: futures::channel::mpsc::UnboundedSender<()>,
+}
+
+impl Connection {
+    /// The channel for sending bytes to the amplifier over this connection at
+    /// [Priority::User](enum.Priority.html#variant.User).
+    pub fn sender(self: &Self) -> futures::channel::mpsc::Sender<Vec<u8>> {
+        self.to_amp.clone()
+    }
+
+    /// The channel for sending bytes to the amplifier over this connection at
+    /// [Priority::Polling](enum.Priority.html#variant.Polling).
+    pub fn polling_sender(self: &Self) -> futures::channel::mpsc::Sender<Vec<u8>> {
+        self.polling_to_amp.clone()
+    }
+
+    /// Change the minimum gap between sends to the amplifier, for example when the model of
+    /// amplifier becomes known.
+    pub fn set_minimum_gap(self: &Self, gap: Duration) {
+        debug!("Connection::set_minimum_gap:  Setting minimum gap to {:?}.", gap);
+        self.minimum_gap.set(gap);
+    }
+
+    /// Change the heartbeat of the connection, `None` for no heartbeat, for example when the
+    /// model of amplifier becomes known.
+    ///
+    /// The writer task is woken so that a heartbeat takes effect even if the connection had none.
+    pub fn set_heartbeat(self: &Self, heartbeat: Option<Heartbeat>) {
+        debug!("Connection::set_heartbeat:  Setting heartbeat to {:?}.", heartbeat);
+        *self.heartbeat.borrow_mut() = heartbeat;
+        let _ = self.heartbeat_changed.unbounded_send(());
+    }
+
+    /// Is the connection closed, or closing.
+    pub fn is_closed(self: &Self) -> bool {
+        self.to_amp.is_closed()
+    }
+
+    /// Close the connection.
+    ///
+    /// Closing the channels to the amplifier stops any more bytes being queued for sending,
+    /// for all clones of the senders. The writer task sends whatever is already queued and
+    /// then shuts down the socket and cancels the listener. If waiting to reconnect, there is
+    /// no further attempt.
+    pub fn close(self: &mut Self) {
+        debug!("Connection::close:  Closing the connection.");
+        self.to_amp.close_channel();
+        self.polling_to_amp.close_channel();
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// The priority of bytes to send to the amplifier: user actions go ahead of polling for the
+/// state of the amplifier.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Priority {
+    User,
+    Polling,
+}
+
+/// The bytes to send to the amplifier, from all the senders, with their priority.
+type Outgoing = futures::stream::Fuse<futures::stream::LocalBoxStream<'static, (Priority, Vec<u8>)>>;
+
+/// The queue of bytes waiting to be sent to the amplifier.
+///
+/// Bytes are sent in priority order, first come first served within a priority, with at least
+/// the minimum gap between sends.
+#[derive(Debug)]
+struct Scheduler {
+    user: VecDeque<Vec<u8>>,
+    polling: VecDeque<Vec<u8>>,
+    minimum_gap: Rc<Cell<Duration>>,
+    last_sent: Option<Instant>,
+}
+
+impl Scheduler {
+    fn new(minimum_gap: Rc<Cell<Duration>>) -> Self {
+        Self { user: VecDeque::new(), polling: VecDeque::new(), minimum_gap, last_sent: None }
+    }
+
+    fn push(self: &mut Self, priority: Priority, data: Vec<u8>) {
+        match priority {
+            Priority::User => self.user.push_back(data),
+            Priority::Polling => self.polling.push_back(data),
+        }
+    }
+
+    fn is_empty(self: &Self) -> bool {
+        self.user.is_empty() && self.polling.is_empty()
+    }
+
+    /// Forget everything queued, it is stale once the connection is lost.
+    fn clear(self: &mut Self) {
+        self.user.clear();
+        self.polling.clear();
+    }
+
+    /// How long from `now` until the next bytes can be sent, `None` if there is nothing to send.
+    fn wait(self: &Self, now: Instant) -> Option<Duration> {
+        if self.is_empty() { return None; }
+        Some(match self.last_sent {
+            Some(last_sent) => (last_sent + self.minimum_gap.get()).saturating_duration_since(now),
+            None => Duration::from_secs(0),
+        })
+    }
+
+    /// The next bytes to send, if they can be sent at `now`.
+    fn pop(self: &mut Self, now: Instant) -> Option<Vec<u8>> {
+        if self.wait(now) != Some(Duration::from_secs(0)) { return None; }
+        self.last_sent = Some(now);
+        self.user.pop_front().or_else(|| self.polling.pop_front())
+    }
+}
+
+/// The settings for a connection to an amplifier.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConnectionSettings {
+    /// How to reconnect when the connection is lost, `None` for no reconnection.
+    pub reconnection: Option<Reconnection>,
+    /// The smallest gap between sends to the amplifier, the default is no gap.
+    pub minimum_gap: Duration,
+    /// How to check that an idle connection is still alive, `None` for no checking.
+    pub heartbeat: Option<Heartbeat>,
+}
+
+/// How to check that a connection to an amplifier is still alive: whenever nothing has been
+/// heard from the amplifier for the interval the request is sent, at polling priority, and if
+/// the amplifier does not answer `missed_limit` requests in a row the connection is lost.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Heartbeat {
+    pub request: Vec<u8>,
+    pub interval: Duration,
+    pub missed_limit: u32,
+}
+
+impl Heartbeat {
+    /// A heartbeat sending `request` after 10 s of silence, the connection being lost after
+    /// 2 missed answers.
+    pub fn new(request: Vec<u8>) -> Self {
+        Self { request, interval: Duration::from_secs(10), missed_limit: 2 }
+    }
+}
+
+/// What needs doing next to keep a heartbeat.
+#[derive(Debug, Eq, PartialEq)]
+enum Beat {
+    /// Nothing until after the delay.
+    Wait(Duration),
+    /// Send the heartbeat request.
+    Send(Vec<u8>),
+    /// Too many heartbeat requests in a row have gone unanswered.
+    Lost,
+}
+
+/// The state of the heartbeat of an established connection.
+///
+/// Anything at all heard from the amplifier counts as an answer to a heartbeat request.
+#[derive(Debug)]
+struct HeartbeatMonitor {
+    heartbeat: Rc<RefCell<Option<Heartbeat>>>,
+    last_heard: Rc<Cell<Instant>>,
+    last_sent: Option<Instant>,
+    missed: u32,
+}
+
+impl HeartbeatMonitor {
+    fn new(heartbeat: Rc<RefCell<Option<Heartbeat>>>, last_heard: Rc<Cell<Instant>>) -> Self {
+        Self { heartbeat, last_heard, last_sent: None, missed: 0 }
+    }
+
+    /// What needs doing at `now`, `None` if there is no heartbeat.
+    fn check(self: &mut Self, now: Instant) -> Option<Beat> {
+        let heartbeat = self.heartbeat.borrow();
+        let heartbeat = heartbeat.as_ref()?;
+        if let Some(last_sent) = self.last_sent {
+            if self.last_heard.get() >= last_sent {
+                self.last_sent = None;
+                self.missed = 0;
+            }
+        }
+        let due = self.last_sent.unwrap_or_else(|| self.last_heard.get()) + heartbeat.interval;
+        if now < due { return Some(Beat::Wait(due - now)); }
+        if self.last_sent.is_some() {
+            self.missed += 1;
+            if self.missed >= heartbeat.missed_limit { return Some(Beat::Lost); }
+        }
+        self.last_sent = Some(now);
+        Some(Beat::Send(heartbeat.request.clone()))
+    }
+}
+
+/// How to reconnect to an amplifier when a connection is lost: the delay before the first
+/// attempt, doubling for each attempt that fails up to the maximum delay.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Reconnection {
+    pub initial_delay: Duration,
+    pub maximum_delay: Duration,
+}
+
+impl Default for Reconnection {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            maximum_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl Reconnection {
+    /// The delay before the next attempt to reconnect given the delay before the previous
+    /// attempt, if there was one.
+    pub fn next_delay(self: &Self, delay: Option<Duration>) -> Duration {
+        match delay {
+            Some(d) => (d * 2).min(self.maximum_delay),
+            None => self.initial_delay,
+        }
+    }
+}
+
+/// The states of a connection to an amplifier, sent from here to the UI as they happen.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConnectionEvent {
+    /// Looking up the address of the amplifier.
+    Resolving,
+    /// Trying to connect to the amplifier.
+    Connecting,
+    /// Connected to the amplifier.
+    Connected,
+    /// The connection could not be made, with the reason why.
+    Failed(String),
+    /// An established connection was broken other than by a close, with the reason why.
+    Lost(String),
+    /// The connection was lost, or an attempt to reconnect failed, with the reason why, and
+    /// there will be another attempt to connect after the delay.
+    Reconnecting { reason: String, delay: Duration },
+    /// The connection was closed.
+    Closed,
+}
+
+impl fmt::Display for ConnectionEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Resolving => write!(f, "Resolving"),
+            Self::Connecting => write!(f, "Connecting"),
+            Self::Connected => write!(f, "Connected"),
+            Self::Failed(reason) => write!(f, "Failed to connect – {}", reason),
+            Self::Lost(reason) => write!(f, "Connection lost – {}", reason),
+            Self::Reconnecting { reason, delay } => write!(f, "Connection lost – {}, reconnecting in {:.1} s", reason, delay.as_secs_f32()),
+            Self::Closed => write!(f, "Not Connected"),
+        }
+    }
+}
+
+impl ConnectionEvent {
+    /// Is this the last event for a connection.
+    pub fn ends_connection(self: &Self) -> bool {
+        match self {
+            Self::Failed(_) | Self::Lost(_) | Self::Closed => true,
+            _ => false,
+        }
+    }
+}
+
+/// A short description, for the user, of why an address lookup or a connection failed.
+fn failure_reason(error: &glib::Error) -> String {
+    if let Some(kind) = error.kind::<gio::ResolverError>() {
+        return match kind {
+            gio::ResolverError::NotFound => "address not found".to_string(),
+            gio::ResolverError::TemporaryFailure => "address lookup failed".to_string(),
+            _ => error.to_string(),
+        };
+    }
+    match error.kind::<gio::IOErrorEnum>() {
+        Some(gio::IOErrorEnum::ConnectionRefused) => "connection refused".to_string(),
+        Some(gio::IOErrorEnum::HostUnreachable) | Some(gio::IOErrorEnum::NetworkUnreachable) => "unreachable".to_string(),
+        Some(gio::IOErrorEnum::TimedOut) => "timed out".to_string(),
+        _ => error.to_string(),
+    }
+}
+
+/// Send a [ConnectionEvent](enum.ConnectionEvent.html) to the UI.
+fn send_event(connection_events: &glib::Sender<ConnectionEvent>, event: ConnectionEvent) {
+    debug!("send_event:  Connection event {:?}.", event);
+    if let Err(e) = connection_events.send(event) {
+        debug!("send_event:  Failed to send connection event – {:?}.", e);
+    }
+}
+
+/// Forward bytes from the amplifier to the UI until the connection breaks, returning the
+/// reason it broke. The time anything was last heard from the amplifier is recorded in
+/// `last_heard`.
 async fn listen_to_reader(
     mut reader: futures::io::ReadHalf<SocketConnection>,
-    from_comms_manager: glib::Sender<Vec<u8>>
-) {
+    from_comms_manager: glib::Sender<Vec<u8>>,
+    last_heard: Rc<Cell<Instant>>,
+) -> String {
     // TODO should the byte sequence parsing happen here or elsewhere?
     let mut buffer = [0u8; 256];
     debug!("listen_to_reader:  Entering listen loop.");
     loop {
-        // TODO How to disconnect this listener when the connection is closed?
         let count = match reader.read(&mut buffer).await {
             Ok(s) => {
                 debug!("listen_to_reader:  Got a packet: {:?}.", &buffer[..s]);
@@ -61,11 +390,11 @@ async fn listen_to_reader(
             },
             Err(e) => {
                 debug!("listen_to_reader:  Failed to read – {:?}.", e);
-                0
+                return e.to_string();
             },
         };
-        //  TODO what happens if the amp is switched off (or put to sleep) during a connection?
-        if count == 0 { break; }
+        if count == 0 { return "closed by the amplifier".to_string(); }
+        last_heard.set(Instant::now());
         match from_comms_manager.send(buffer[..count].to_vec()) {
             Ok(_) => {},
             Err(e) => debug!("listen_to_reader:  Failed to send packet – {:?}.", e),
@@ -73,63 +402,316 @@ async fn listen_to_reader(
     }
 }
 
-async fn start_a_connection_and_set_up_event_listeners(
-    to_control_window: glib::Sender<Vec<u8>>,
-    mut to_comms_manager: futures::channel::mpsc::Receiver<Vec<u8>>,
-    address: gio::NetworkAddress,
-) {
-    debug!("start_a_connection_and_set_up_event_listeners:  Setting up connection to {}:{}.", address.get_hostname().unwrap(), address.get_port());
+/// Connect to the first of the addresses found for the host that accepts a connection.
+async fn connect_to_address(
+    connection_events: &glib::Sender<ConnectionEvent>,
+    hostname: &str,
+    port_number: u16,
+) -> Result<SocketConnection, String> {
+    send_event(connection_events, ConnectionEvent::Resolving);
+    let addresses = gio::Resolver::get_default().lookup_by_name_async_future(hostname).await
+        .map_err(|e| failure_reason(&e))?;
     let client = SocketClient::new();
-    let connection = match client.connect(&address).await {
-        Ok(s) => {
-            debug!("start_a_connection_and_set_up_event_listeners:  Connected to {}:{}.", address.get_hostname().unwrap(), address.get_port());
-            s
-        },
-        Err(_) => {
-            debug!("start_a_connection_and_set_up_event_listeners:  Failed to connect to {}:{}.", address.get_hostname().unwrap(), address.get_port());
-            return
-        },
-    };
+    let mut reason = "address not found".to_string();
+    for address in addresses.iter() {
+        send_event(connection_events, ConnectionEvent::Connecting);
+        match client.connect(&gio::InetSocketAddress::new(address, port_number)).await {
+            Ok(connection) => return Ok(connection),
+            Err(e) => {
+                debug!("connect_to_address:  Failed to connect to {}:{} – {}.", address.to_str(), port_number, e);
+                reason = failure_reason(&e);
+            },
+        }
+    }
+    Err(reason)
+}
+
+/// How an established connection ended.
+enum Ending {
+    Closed,
+    Lost(String),
+}
+
+/// Write bytes to the amplifier, as scheduled, with a listener forwarding bytes from the
+/// amplifier, until either the channels to the amplifier are closed and everything queued is
+/// written, or the connection breaks, or the amplifier stops answering the heartbeat.
+async fn use_connection(
+    connection: SocketConnection,
+    to_control_window: &glib::Sender<Vec<u8>>,
+    outgoing: &mut Outgoing,
+    scheduler: &mut Scheduler,
+    heartbeat: &Rc<RefCell<Option<Heartbeat>>>,
+    heartbeat_changed: &mut futures::channel::mpsc::UnboundedReceiver<()>,
+) -> Ending {
     let (reader, mut writer) = connection.split();
-    let context = glib::MainContext::default();
-    context.spawn_local(async move {
-        while let Some(data) = to_comms_manager.next().await {
-            debug!("start_a_connection_and_set_up_event_listeners:  Writing {:?}", &data);
+    let (abort_handle, abort_registration) = AbortHandle::new_pair();
+    let (tx_lost, mut rx_lost) = futures::channel::oneshot::channel();
+    let last_heard = Rc::new(Cell::new(Instant::now()));
+    let mut monitor = HeartbeatMonitor::new(heartbeat.clone(), last_heard.clone());
+    glib::MainContext::default().spawn_local({
+        let to_control_window = to_control_window.clone();
+        async move {
+            match Abortable::new(listen_to_reader(reader, to_control_window, last_heard), abort_registration).await {
+                Ok(reason) => { let _ = tx_lost.send(reason); },
+                Err(_) => debug!("use_connection:  Listener cancelled."),
+            }
+        }
+    });
+    let mut closing = false;
+    let ending = loop {
+        if let Some(data) = scheduler.pop(Instant::now()) {
+            debug!("use_connection:  Writing {:?}", &data);
             match writer.write_all(&data).await {
-                Ok(_) => { debug!("start_a_connection_and_set_up_event_listeners:  Successfully sent packet to amp {:?}.", data); },
+                Ok(_) => { debug!("use_connection:  Successfully sent packet to amp {:?}.", data); },
                 Err(e) => {
-                    // TODO Must think about showing disconnection in the UI when this happens.
-                    debug!("start_a_connection_and_set_up_event_listeners:  Error sending packet to amp {:?}.", e);
+                    debug!("use_connection:  Error sending packet to amp {:?}.", e);
+                    break Ending::Lost(e.to_string());
                 },
             };
+            continue;
         }
-    });
-    context.spawn_local(listen_to_reader(reader, to_control_window));
-    debug!("start_a_connection_and_set_up_event_listeners:  Set up connection to {:?}.", address);
+        // The channels are closed and all queued data has been written.
+        if closing && scheduler.is_empty() {
+            debug!("use_connection:  Closing the socket.");
+            if let Err(e) = writer.close().await {
+                debug!("use_connection:  Error closing the socket – {:?}.", e);
+            }
+            break Ending::Closed;
+        }
+        let mut heartbeat_due = match if closing { None } else { monitor.check(Instant::now()) } {
+            Some(Beat::Wait(delay)) => glib::timeout_future(delay).fuse(),
+            Some(Beat::Send(data)) => {
+                debug!("use_connection:  Nothing heard from the amp, sending heartbeat.");
+                scheduler.push(Priority::Polling, data);
+                continue;
+            },
+            Some(Beat::Lost) => break Ending::Lost("no answer to heartbeat".to_string()),
+            None => Fuse::terminated(),
+        };
+        let mut gap = match scheduler.wait(Instant::now()) {
+            Some(delay) => glib::timeout_future(delay).fuse(),
+            None => Fuse::terminated(),
+        };
+        futures::select! {
+            item = outgoing.next() => match item {
+                Some((priority, data)) => scheduler.push(priority, data),
+                None => closing = true,
+            },
+            reason = rx_lost => break Ending::Lost(reason.unwrap_or_else(|_| "listener failed".to_string())),
+            _ = gap => {},
+            _ = heartbeat_due => {},
+            _ = heartbeat_changed.next() => {},
+        }
+    };
+    abort_handle.abort();
+    ending
+}
+
+/// Wait before trying to reconnect, discarding anything sent for the amplifier in the meantime.
+/// Returns false if the channels to the amplifier are closed whilst waiting.
+async fn wait_to_reconnect(outgoing: &mut Outgoing, delay: Duration) -> bool {
+    let mut timeout = glib::timeout_future(delay);
+    loop {
+        match future::select(outgoing.next(), &mut timeout).await {
+            Either::Left((Some(data), _)) => debug!("wait_to_reconnect:  Not connected, discarding {:?}.", data),
+            Either::Left((None, _)) => return false,
+            Either::Right(_) => return true,
+        }
+    }
+}
+
+async fn start_a_connection_and_set_up_event_listeners(
+    to_control_window: glib::Sender<Vec<u8>>,
+    connection_events: glib::Sender<ConnectionEvent>,
+    mut outgoing: Outgoing,
+    mut scheduler: Scheduler,
+    hostname: String,
+    port_number: u16,
+    reconnection: Option<Reconnection>,
+    heartbeat: Rc<RefCell<Option<Heartbeat>>>,
+    mut heartbeat_changed: futures::channel::mpsc::UnboundedReceiver<()>,
+) {
+    debug!("start_a_connection_and_set_up_event_listeners:  Setting up connection to {}:{}.", hostname, port_number);
+    // The delay before the last attempt to reconnect, None if not reconnecting.
+    let mut delay = None;
+    loop {
+        let reason = match connect_to_address(&connection_events, &hostname, port_number).await {
+            Ok(connection) => {
+                debug!("start_a_connection_and_set_up_event_listeners:  Connected to {}:{}.", hostname, port_number);
+                send_event(&connection_events, ConnectionEvent::Connected);
+                delay = None;
+                match use_connection(connection, &to_control_window, &mut outgoing, &mut scheduler, &heartbeat, &mut heartbeat_changed).await {
+                    Ending::Closed => {
+                        send_event(&connection_events, ConnectionEvent::Closed);
+                        return
+                    },
+                    Ending::Lost(reason) => {
+                        debug!("start_a_connection_and_set_up_event_listeners:  Lost connection to {}:{} – {}.", hostname, port_number, reason);
+                        scheduler.clear();
+                        if reconnection.is_none() {
+                            send_event(&connection_events, ConnectionEvent::Lost(reason));
+                            return
+                        }
+                        reason
+                    },
+                }
+            },
+            Err(reason) => {
+                debug!("start_a_connection_and_set_up_event_listeners:  Failed to connect to {}:{} – {}.", hostname, port_number, reason);
+                // Only attempts to reconnect are retried, failing to make a first connection is final.
+                if delay.is_none() {
+                    send_event(&connection_events, ConnectionEvent::Failed(reason));
+                    return
+                }
+                reason
+            },
+        };
+        let next_delay = reconnection.unwrap_or_default().next_delay(delay);
+        delay = Some(next_delay);
+        send_event(&connection_events, ConnectionEvent::Reconnecting { reason, delay: next_delay });
+        if !wait_to_reconnect(&mut outgoing, next_delay).await {
+            send_event(&connection_events, ConnectionEvent::Closed);
+            return
+        }
+    }
 }
 
 /// Connect to an Arcam amp at the address given.
+///
+/// The returned [Connection](struct.Connection.html) must be kept for as long as the connection
+/// is wanted, dropping it closes the connection. Whether the connection is actually made, and
+/// what happens to it subsequently, is reported as
+/// [ConnectionEvent](enum.ConnectionEvent.html)s sent down `connection_events`.
+///
+/// If the [ConnectionSettings](struct.ConnectionSettings.html) have a reconnection then a
+/// connection that is lost is reconnected, the same [Connection](struct.Connection.html)
+/// continues to be used.
 pub fn connect_to_amp(
     to_control_window: &glib::Sender<Vec<u8>>,
+    connection_events: &glib::Sender<ConnectionEvent>,
     address: &str,
-    port_number: u16
-) -> Result<futures::channel::mpsc::Sender<Vec<u8>>, String> {
-    // TODO This appears to always connect when in fact it doesn't.
-    //   Need to find a way of messaging the functionality and control_window as to
-    //   whether a connection was actually made or not.
-    debug!("connect_to_amp:  Connecting to {:?}:{:?}.", address, port_number);
-    let (tx_to_comms_manager, rx_to_comms_manager) = futures::channel::mpsc::channel(10);
+    port_number: u16,
+    settings: ConnectionSettings,
+) -> Result<Connection, String> {
+    debug!("connect_to_amp:  Connecting to {:?}:{:?} with {:?}.", address, port_number, settings);
+    if address.is_empty() { return Err("No address to connect to.".to_string()); }
+    // Everything sent at once, such as when initialising, must fit in the channels.
+    let (tx_to_comms_manager, rx_to_comms_manager) = futures::channel::mpsc::channel(64);
+    let (tx_polling_to_comms_manager, rx_polling_to_comms_manager) = futures::channel::mpsc::channel(64);
+    let outgoing = futures::stream::select(
+        rx_to_comms_manager.map(|data| (Priority::User, data)),
+        rx_polling_to_comms_manager.map(|data| (Priority::Polling, data)),
+    ).boxed_local().fuse();
+    let minimum_gap = Rc::new(Cell::new(settings.minimum_gap));
+    let heartbeat = Rc::new(RefCell::new(settings.heartbeat));
+    let (tx_heartbeat_changed, rx_heartbeat_changed) = futures::channel::mpsc::unbounded();
     glib::MainContext::default().spawn_local(
         start_a_connection_and_set_up_event_listeners(
             to_control_window.clone(),
-            rx_to_comms_manager,
-            gio::NetworkAddress::new(address, port_number),
+            connection_events.clone(),
+            outgoing,
+            Scheduler::new(minimum_gap.clone()),
+            address.to_string(),
+            port_number,
+            settings.reconnection,
+            heartbeat.clone(),
+            rx_heartbeat_changed,
         )
     );
-    Ok(tx_to_comms_manager)
+    Ok(Connection{
+        to_amp: tx_to_comms_manager,
+        polling_to_amp: tx_polling_to_comms_manager,
+        minimum_gap,
+        heartbeat,
+        heartbeat_changed: tx_heartbeat_changed,
+    })
 }
 
-/// Terminate the current connection.
-pub fn disconnect_from_amp() {
+/// Terminate a connection, flushing any pending writes, shutting down the socket, and
+/// cancelling the listener.
+pub fn disconnect_from_amp(mut connection: Connection) {
+    debug!("disconnect_from_amp:  Disconnecting.");
+    connection.close();
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnection_delay_doubles_up_to_the_maximum() {
+        let reconnection = Reconnection { initial_delay: Duration::from_secs(1), maximum_delay: Duration::from_secs(5) };
+        assert_eq!(reconnection.next_delay(None), Duration::from_secs(1));
+        assert_eq!(reconnection.next_delay(Some(Duration::from_secs(1))), Duration::from_secs(2));
+        assert_eq!(reconnection.next_delay(Some(Duration::from_secs(2))), Duration::from_secs(4));
+        assert_eq!(reconnection.next_delay(Some(Duration::from_secs(4))), Duration::from_secs(5));
+        assert_eq!(reconnection.next_delay(Some(Duration::from_secs(5))), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn reconnecting_is_not_the_end_of_a_connection() {
+        let event = ConnectionEvent::Reconnecting { reason: "closed by the amplifier".to_string(), delay: Duration::from_secs(2) };
+        assert!(!event.ends_connection());
+        assert_eq!(event.to_string(), "Connection lost – closed by the amplifier, reconnecting in 2.0 s");
+        let event = ConnectionEvent::Reconnecting { reason: "closed by the amplifier".to_string(), delay: Duration::from_millis(100) };
+        assert_eq!(event.to_string(), "Connection lost – closed by the amplifier, reconnecting in 0.1 s");
+        assert!(ConnectionEvent::Lost("closed by the amplifier".to_string()).ends_connection());
+    }
+
+    #[test]
+    fn scheduler_sends_user_actions_before_polling() {
+        let mut scheduler = Scheduler::new(Rc::new(Cell::new(Duration::from_millis(0))));
+        let now = Instant::now();
+        assert_eq!(scheduler.wait(now), None);
+        scheduler.push(Priority::Polling, vec![1]);
+        scheduler.push(Priority::User, vec![2]);
+        scheduler.push(Priority::Polling, vec![3]);
+        assert_eq!(scheduler.pop(now), Some(vec![2]));
+        assert_eq!(scheduler.pop(now), Some(vec![1]));
+        assert_eq!(scheduler.pop(now), Some(vec![3]));
+        assert_eq!(scheduler.pop(now), None);
+    }
+
+    #[test]
+    fn scheduler_keeps_the_minimum_gap() {
+        let minimum_gap = Rc::new(Cell::new(Duration::from_millis(225)));
+        let mut scheduler = Scheduler::new(minimum_gap.clone());
+        let now = Instant::now();
+        scheduler.push(Priority::User, vec![1]);
+        scheduler.push(Priority::User, vec![2]);
+        assert_eq!(scheduler.wait(now), Some(Duration::from_millis(0)));
+        assert_eq!(scheduler.pop(now), Some(vec![1]));
+        assert_eq!(scheduler.wait(now + Duration::from_millis(100)), Some(Duration::from_millis(125)));
+        assert_eq!(scheduler.pop(now + Duration::from_millis(100)), None);
+        minimum_gap.set(Duration::from_millis(50));
+        assert_eq!(scheduler.pop(now + Duration::from_millis(100)), Some(vec![2]));
+    }
+
+    #[test]
+    fn heartbeat_is_sent_when_nothing_is_heard() {
+        let now = Instant::now();
+        let heartbeat = Heartbeat { request: vec![0x25], interval: Duration::from_secs(10), missed_limit: 2 };
+        let last_heard = Rc::new(Cell::new(now));
+        let mut monitor = HeartbeatMonitor::new(Rc::new(RefCell::new(Some(heartbeat))), last_heard.clone());
+        assert_eq!(monitor.check(now + Duration::from_secs(4)), Some(Beat::Wait(Duration::from_secs(6))));
+        last_heard.set(now + Duration::from_secs(5));
+        assert_eq!(monitor.check(now + Duration::from_secs(10)), Some(Beat::Wait(Duration::from_secs(5))));
+        assert_eq!(monitor.check(now + Duration::from_secs(15)), Some(Beat::Send(vec![0x25])));
+        assert_eq!(monitor.check(now + Duration::from_secs(16)), Some(Beat::Wait(Duration::from_secs(9))));
+        // The answer resets the wait.
+        last_heard.set(now + Duration::from_secs(16));
+        assert_eq!(monitor.check(now + Duration::from_secs(20)), Some(Beat::Wait(Duration::from_secs(6))));
+    }
+
+    #[test]
+    fn heartbeat_missed_too_often_loses_the_connection() {
+        let now = Instant::now();
+        let heartbeat = Rc::new(RefCell::new(Some(Heartbeat { request: vec![0x25], interval: Duration::from_secs(10), missed_limit: 2 })));
+        let mut monitor = HeartbeatMonitor::new(heartbeat.clone(), Rc::new(Cell::new(now)));
+        assert_eq!(monitor.check(now + Duration::from_secs(10)), Some(Beat::Send(vec![0x25])));
+        assert_eq!(monitor.check(now + Duration::from_secs(20)), Some(Beat::Send(vec![0x25])));
+        assert_eq!(monitor.check(now + Duration::from_secs(30)), Some(Beat::Lost));
+        *heartbeat.borrow_mut() = None;
+        assert_eq!(monitor.check(now + Duration::from_secs(40)), None);
+    }
 }
@@ -20,7 +20,7 @@
 //! This module provides all the structs, enums and functions associated with display and
 //! control of the UI.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::str::FromStr;
 
@@ -33,9 +33,15 @@ use gtk::prelude::*;
 
 use log::debug;
 
+use arcam_protocol::amp_model::Model;
+use arcam_protocol::{
+    AmpCommand, AnswerError, AudioFormat, Brightness, ChannelConfiguration, Command, DeviceInfo, ErrorAnswerCode, MuteState, PacketDecoder, PowerState,
+    Request, SampleRate, SoftwareVersion, Source, VideoParameters, Volume, ZoneNumber, REQUEST_QUERY,
+};
+
 use crate::about;
-use crate::functionality;
-use crate::arcam_protocol::{Brightness, MuteState, PowerState, Source, ZoneNumber};
+use crate::comms_manager::{Connection, ConnectionEvent, ConnectionSettings, Reconnection};
+use crate::functionality::{self, AmpSender};
 
 /// An analogue to bool that tries to avoid any spelling errors
 /// in the strings used as representation – needed for the UI.
@@ -73,6 +79,15 @@ impl From<bool> for ConnectedState {
     }
 }
 
+impl From<&ConnectionEvent> for ConnectedState {
+    fn from(event: &ConnectionEvent) -> Self {
+        match event {
+            ConnectionEvent::Connected => Self::Connected,
+            _ => Self::NotConnected,
+        }
+    }
+}
+
 impl From<ConnectedState> for bool {
     fn from(c: ConnectedState) -> Self {
         match c {
@@ -88,13 +103,15 @@ impl From<ConnectedState> for bool {
 /// of the amplifier, some components are controllers for causing data to be sent
 /// to the amplifier to change the state.
 ///
-/// This struct also keeps track of the send end of the channel down which to send
-/// data to be forwarded to the amplifier.
+/// This struct also keeps track of the connection to the amplifier and the send end of the
+/// channel down which to send data to be forwarded to the amplifier.
 pub struct ControlWindow {
     window: gtk::ApplicationWindow,
     address: gtk::Entry,
     connect_display: gtk::Label,
     connect_chooser: gtk::CheckButton,
+    reconnect_chooser: gtk::CheckButton,
+    device_display: gtk::Label,
     brightness_display: gtk::Label,
     brightness_chooser: gtk::ComboBoxText,
     zone_1_power_display: gtk::Label,
@@ -121,7 +138,24 @@ pub struct ControlWindow {
     zone_2_radio_station_display: gtk::Label,
     zone_2_music_type_display: gtk::Label,
     zone_2_dlspdt_information_display: gtk::Label,
-    to_comms_manager: RefCell<Option<futures::channel::mpsc::Sender<Vec<u8>>>>,
+    audio_format_display: gtk::Label,
+    audio_channels_display: gtk::Label,
+    audio_sample_rate_display: gtk::Label,
+    video_info: gtk::Box,
+    video_resolution_display: gtk::Label,
+    video_aspect_ratio_display: gtk::Label,
+    video_colourspace_display: gtk::Label,
+    answer_error_bar: gtk::InfoBar,
+    answer_error_display: gtk::Label,
+    to_comms_manager: RefCell<Option<AmpSender>>,
+    connection: RefCell<Option<Connection>>,
+    connection_generation: Cell<u32>,
+    connected_state: Cell<ConnectedState>,
+    updating_connect_chooser: Cell<bool>,
+    stale: Cell<bool>,
+    model: Cell<Model>,
+    device_info: RefCell<Option<DeviceInfo>>,
+    software_versions: RefCell<Vec<SoftwareVersion>>,
 }
 
 impl ControlWindow {
@@ -163,6 +197,8 @@ impl ControlWindow {
         let address: gtk::Entry = builder.get_object("address").unwrap();
         let connect_display: gtk::Label = builder.get_object("connect_display").unwrap();
         let connect_chooser: gtk::CheckButton = builder.get_object("connect_chooser").unwrap();
+        let reconnect_chooser: gtk::CheckButton = builder.get_object("reconnect_chooser").unwrap();
+        let device_display: gtk::Label = builder.get_object("device_display").unwrap();
         let brightness_display: gtk::Label = builder.get_object("brightness_display").unwrap();
         let brightness_chooser: gtk::ComboBoxText = builder.get_object("brightness_chooser").unwrap();
         let zone_1_power_display: gtk::Label = builder.get_object("zone_1_power_display").unwrap();
@@ -189,11 +225,23 @@ impl ControlWindow {
         let zone_2_radio_station_display: gtk::Label = builder.get_object("zone_2_radio_station_display").unwrap();
         let zone_2_music_type_display: gtk::Label = builder.get_object("zone_2_music_type_display").unwrap();
         let zone_2_dlspdt_information_display: gtk::Label = builder.get_object("zone_2_DLSPDT_information_display").unwrap();
+        let audio_format_display: gtk::Label = builder.get_object("audio_format_display").unwrap();
+        let audio_channels_display: gtk::Label = builder.get_object("audio_channels_display").unwrap();
+        let audio_sample_rate_display: gtk::Label = builder.get_object("audio_sample_rate_display").unwrap();
+        let video_info: gtk::Box = builder.get_object("video_info").unwrap();
+        let video_resolution_display: gtk::Label = builder.get_object("video_resolution_display").unwrap();
+        let video_aspect_ratio_display: gtk::Label = builder.get_object("video_aspect_ratio_display").unwrap();
+        let video_colourspace_display: gtk::Label = builder.get_object("video_colourspace_display").unwrap();
+        let answer_error_bar: gtk::InfoBar = builder.get_object("answer_error_bar").unwrap();
+        let answer_error_display: gtk::Label = builder.get_object("answer_error_display").unwrap();
+        answer_error_bar.connect_response(|bar, _| bar.hide());
         let control_window = Rc::new(ControlWindow {
             window,
             address,
             connect_display,
             connect_chooser,
+            reconnect_chooser,
+            device_display,
             brightness_display,
             brightness_chooser,
             zone_1_power_display,
@@ -220,23 +268,46 @@ impl ControlWindow {
             zone_2_radio_station_display,
             zone_2_music_type_display,
             zone_2_dlspdt_information_display,
+            audio_format_display,
+            audio_channels_display,
+            audio_sample_rate_display,
+            video_info,
+            video_resolution_display,
+            video_aspect_ratio_display,
+            video_colourspace_display,
+            answer_error_bar,
+            answer_error_display,
             to_comms_manager: RefCell::new(None),
+            connection: RefCell::new(None),
+            connection_generation: Cell::new(0),
+            connected_state: Cell::new(ConnectedState::NotConnected),
+            updating_connect_chooser: Cell::new(false),
+            stale: Cell::new(false),
+            model: Cell::new(Model::default()),
+            device_info: RefCell::new(None),
+            software_versions: RefCell::new(Vec::new()),
         });
+        control_window.set_model(Model::default());
+        let about_amplifier_action = gio::SimpleAction::new("about_amplifier", None);
+        about_amplifier_action.connect_activate({
+            let c_w = control_window.clone();
+            move |_, _| about::present_amplifier(Some(&c_w.window), c_w.device_info.borrow().as_ref(), &c_w.software_versions.borrow())
+        });
+        control_window.window.add_action(&about_amplifier_action);
         let (tx_from_comms_manager, rx_from_comms_manager) = glib::MainContext::channel(glib::source::PRIORITY_DEFAULT);
         rx_from_comms_manager.attach(None, {
             let c_w = control_window.clone();
-            let mut queue = vec![];
+            let mut decoder = PacketDecoder::new();
             move |datum: Vec<u8>| {  //  TODO Why is this type specification required?
-                for c in datum.iter() {
-                    queue.push(*c);
-                }
-                while functionality::try_parse_of_response_data(&c_w, &mut queue) {}
+                functionality::handle_response_data(&c_w, &mut decoder, &datum);
                 Continue(true)
             }
         });
         control_window.connect_chooser.connect_toggled({
             let c_w = control_window.clone();
             move |button| {
+                // Showing the state of the connection changes the button, that must not connect or disconnect.
+                if c_w.updating_connect_chooser.get() { return; }
                 // NB this is the state after the UI activity that caused the event that called the closure.
                 if button.get_active() {
                     /*
@@ -260,7 +331,7 @@ impl ControlWindow {
                                 let address = address;
                                 let p_n = match port_number {
                                     Some(p) => p,
-                                    None => 50000
+                                    None => c_w.get_model().default_port()
                                 };
                                 debug!("Connect to {}:{}.", &address, p_n);
                                 match functionality::connect_to_amp(
@@ -268,15 +339,16 @@ impl ControlWindow {
                                     &address.to_string(),
                                     p_n,
                                 ) {
-                                    Ok(s) => {
+                                    Ok(connection) => {
                                         //  TODO How come a mutable borrow works here?
                                         //  TODO Why is the argument to replace here not an Option?
-                                        c_w.to_comms_manager.borrow_mut().replace(s);
+                                        c_w.to_comms_manager.borrow_mut().replace(connection.sender());
+                                        c_w.connection.replace(Some(connection));
                                         debug!("Connected to amp at {}:{}.", address, p_n);
                                     },
                                     Err(e) => debug!("Failed to connect to amp – {:?}.", e),
                                 };
-                                functionality::initialise_control_window(&mut c_w.get_to_comms_manager());
+                                functionality::initialise_control_window(&mut c_w.get_to_comms_manager(), c_w.get_model());
                             }
                         }
                         None => {
@@ -310,29 +382,61 @@ impl ControlWindow {
                         let address = address;
                         let p_n = match port_number {
                             Some(p) => p,
-                            None => 50000
+                            None => c_w.get_model().default_port()
                         };
                         debug!("Connect to {}:{}.", &address, p_n);
+                        // Events from earlier connections are of no interest.
+                        let generation = c_w.connection_generation.get() + 1;
+                        c_w.connection_generation.set(generation);
+                        let (tx_connection_events, rx_connection_events) = glib::MainContext::channel(glib::source::PRIORITY_DEFAULT);
+                        rx_connection_events.attach(None, {
+                            let c_w = c_w.clone();
+                            move |event: ConnectionEvent| {
+                                if c_w.connection_generation.get() == generation {
+                                    functionality::handle_connection_event(&c_w, &event);
+                                }
+                                Continue(!event.ends_connection())
+                            }
+                        });
+                        let settings = ConnectionSettings {
+                            reconnection: if c_w.reconnect_chooser.get_active() { Some(Reconnection::default()) } else { None },
+                            minimum_gap: c_w.get_model().minimum_request_gap(),
+                            heartbeat: functionality::heartbeat(c_w.get_model()),
+                        };
                         match functionality::connect_to_amp(
                             &tx_from_comms_manager,
+                            &tx_connection_events,
                             &address.to_string(),
                             p_n,
+                            settings,
                         ) {
-                            Ok(s) => {
+                            Ok(connection) => {
                                 //  TODO How come a mutable borrow works here?
                                 //  TODO Why is the argument to replace here not an Option?
-                                c_w.to_comms_manager.borrow_mut().replace(s);
-                                debug!("Connected to amp at {}:{}.", address, p_n);
+                                c_w.to_comms_manager.borrow_mut().replace(AmpSender::new(connection.sender()));
+                                c_w.connection.replace(Some(connection));
+                                debug!("Connecting to amp at {}:{}.", address, p_n);
+                            },
+                            Err(e) => {
+                                debug!("Failed to connect to amp – {:?}.", e);
+                                c_w.set_connect_display(&ConnectionEvent::Failed(e));
                             },
-                            Err(e) => debug!("Failed to connect to amp – {:?}.", e),
                         };
-                        functionality::initialise_control_window(&mut c_w.get_to_comms_manager());
                     }
                     // Git API End.
                 } else {
                     debug!("Terminate connection to amp.");
-                    functionality::disconnect_from_amp();
-                    c_w.connect_display.set_text(&ConnectedState::NotConnected.to_string());
+                    c_w.connection_generation.set(c_w.connection_generation.get() + 1);
+                    if let Some(connection) = c_w.connection.borrow_mut().take() {
+                        functionality::disconnect_from_amp(connection);
+                    }
+                    c_w.to_comms_manager.replace(None);
+                    c_w.set_connect_display(&ConnectionEvent::Closed);
+                    c_w.device_display.set_text("");
+                    c_w.device_info.replace(None);
+                    c_w.clear_now_decoding_display();
+                    c_w.software_versions.borrow_mut().clear();
+                    c_w.answer_error_bar.hide();
                 }
             }
         });
@@ -340,7 +444,7 @@ impl ControlWindow {
             let c_w = control_window.clone();
             move |button| {
                 if c_w.is_connected() {
-                    functionality::set_power_on_amp(&mut c_w.get_to_comms_manager(), ZoneNumber::One, button.get_active().into());
+                    functionality::set_power_on_amp(&mut c_w.get_to_comms_manager(), c_w.get_model(), ZoneNumber::One, button.get_active().into());
                 }
             }
         });
@@ -348,7 +452,9 @@ impl ControlWindow {
             let c_w = control_window.clone();
             move |button| {
                 if c_w.is_connected() {
-                    functionality::set_volume_on_amp(&mut c_w.get_to_comms_manager(), ZoneNumber::One, button.get_value() as u8);
+                    if let Ok(volume) = Volume::new(button.get_value() as u8) {
+                        functionality::set_volume_on_amp(&mut c_w.get_to_comms_manager(), c_w.get_model(), ZoneNumber::One, volume);
+                    }
                 }
             }
         });
@@ -356,15 +462,26 @@ impl ControlWindow {
             let c_w = control_window.clone();
             move |button| {
                 if c_w.is_connected() {
-                    functionality::set_mute_on_amp(&mut c_w.get_to_comms_manager(), ZoneNumber::One, button.get_active().into())
+                    functionality::set_mute_on_amp(&mut c_w.get_to_comms_manager(), c_w.get_model(), ZoneNumber::One, button.get_active().into())
                 }
             }
         });
         control_window.zone_1_source_chooser.connect_changed({
             let c_w = control_window.clone();
             move |cbt| {
-                if c_w.is_connected() {
-                    functionality::set_source_on_amp(&mut c_w.get_to_comms_manager(), ZoneNumber::One, Source::from_str(cbt.get_active_id().unwrap().as_ref()).unwrap());
+                // There is no active item while the items are being replaced.
+                if let Some(id) = cbt.get_active_id() {
+                    if c_w.is_connected() {
+                        let source = Source::from_str(id.as_ref()).unwrap();
+                        functionality::set_source_on_amp(&mut c_w.get_to_comms_manager(), c_w.get_model(), ZoneNumber::One, source);
+                        // The display already shows the source if the chooser is following the amplifier
+                        // rather than the user, in which case what is being decoded is not refreshed.
+                        if c_w.zone_1_source_display.get_text().as_str() != id.as_str() {
+                            if let Some(mut sender) = c_w.try_get_polling_sender() {
+                                functionality::get_now_decoding_from_amp(&mut sender, c_w.get_model(), ZoneNumber::One, source.video_source().is_some());
+                            }
+                        }
+                    }
                 }
             }
         });
@@ -372,7 +489,7 @@ impl ControlWindow {
             let c_w = control_window.clone();
             move |button| {
                 if c_w.is_connected() {
-                    functionality::set_power_on_amp(&mut c_w.get_to_comms_manager(), ZoneNumber::Two, button.get_active().into());
+                    functionality::set_power_on_amp(&mut c_w.get_to_comms_manager(), c_w.get_model(), ZoneNumber::Two, button.get_active().into());
                 }
             }
         });
@@ -380,7 +497,9 @@ impl ControlWindow {
             let c_w = control_window.clone();
             move |button| {
                 if c_w.is_connected() {
-                    functionality::set_volume_on_amp(&mut c_w.get_to_comms_manager(), ZoneNumber::Two, button.get_value() as u8);
+                    if let Ok(volume) = Volume::new(button.get_value() as u8) {
+                        functionality::set_volume_on_amp(&mut c_w.get_to_comms_manager(), c_w.get_model(), ZoneNumber::Two, volume);
+                    }
                 }
             }
         });
@@ -388,15 +507,18 @@ impl ControlWindow {
             let c_w = control_window.clone();
             move |button| {
                 if c_w.is_connected() {
-                    functionality::set_mute_on_amp(&mut c_w.get_to_comms_manager(), ZoneNumber::Two, button.get_active().into())
+                    functionality::set_mute_on_amp(&mut c_w.get_to_comms_manager(), c_w.get_model(), ZoneNumber::Two, button.get_active().into())
                 }
             }
         });
         control_window.zone_2_source_chooser.connect_changed({
             let c_w = control_window.clone();
             move |cbt| {
-                if c_w.is_connected() {
-                    functionality::set_source_on_amp(&mut c_w.get_to_comms_manager(), ZoneNumber::Two, Source::from_str(cbt.get_active_id().unwrap().as_ref()).unwrap());
+                // There is no active item while the items are being replaced.
+                if let Some(id) = cbt.get_active_id() {
+                    if c_w.is_connected() {
+                        functionality::set_source_on_amp(&mut c_w.get_to_comms_manager(), c_w.get_model(), ZoneNumber::Two, Source::from_str(id.as_ref()).unwrap());
+                    }
                 }
             }
         });
@@ -404,17 +526,156 @@ impl ControlWindow {
     }
 
     /// Accessor for the send end of the channel to send data to the amplifier via the comms manager.
-    fn get_to_comms_manager(self: &Self) -> futures::channel::mpsc::Sender<Vec<u8>> {
+    fn get_to_comms_manager(self: &Self) -> AmpSender {
         self.to_comms_manager.borrow().as_ref().unwrap().clone()
     }
 
+    /// Accessor for the [Model](../../arcam_protocol/amp_model/enum.Model.html) of amplifier being controlled.
+    pub fn get_model(self: &Self) -> Model {
+        self.model.get()
+    }
+
+    /// Set the [Model](../../arcam_protocol/amp_model/enum.Model.html) of amplifier being controlled, changing
+    /// the source choosers to offer only the sources the model has and hiding the displays and
+    /// choosers for commands the model does not understand.
+    pub fn set_model(self: &Self, model: Model) {
+        debug!("set_model:  Setting model to {}.", model);
+        self.model.set(model);
+        if let Some(connection) = self.connection.borrow().as_ref() {
+            connection.set_minimum_gap(model.minimum_request_gap());
+            connection.set_heartbeat(functionality::heartbeat(model));
+        }
+        for (zone, source_chooser) in [(ZoneNumber::One, &self.zone_1_source_chooser), (ZoneNumber::Two, &self.zone_2_source_chooser)].iter() {
+            let active_id = source_chooser.get_active_id();
+            source_chooser.remove_all();
+            for source in model.sources().iter() {
+                if !source.applies_to_zone(*zone) { continue; }
+                let label = match source {
+                    Source::TUNER if model.supports_source(Source::TUNERDAB) => "TUNER FM".to_string(),
+                    Source::TUNER => "TUNER FM/DAB".to_string(),
+                    Source::TUNERDAB => "TUNER DAB".to_string(),
+                    s => s.to_string(),
+                };
+                source_chooser.append(Some(&source.to_string()), &label);
+            }
+            if let Some(id) = active_id {
+                source_chooser.set_active_id(Some(&id));
+            }
+        }
+        let displays: &[(&gtk::Widget, ZoneNumber, Command)] = &[
+            (self.brightness_display.upcast_ref(), ZoneNumber::One, Command::DisplayBrightness),
+            (self.zone_1_power_display.upcast_ref(), ZoneNumber::One, Command::Power),
+            (self.zone_1_volume_display.upcast_ref(), ZoneNumber::One, Command::SetRequestVolume),
+            (self.zone_1_mute_display.upcast_ref(), ZoneNumber::One, Command::RequestMuteStatus),
+            (self.zone_1_source_display.upcast_ref(), ZoneNumber::One, Command::RequestCurrentSource),
+            (self.zone_2_power_display.upcast_ref(), ZoneNumber::Two, Command::Power),
+            (self.zone_2_volume_display.upcast_ref(), ZoneNumber::Two, Command::SetRequestVolume),
+            (self.zone_2_mute_display.upcast_ref(), ZoneNumber::Two, Command::RequestMuteStatus),
+            (self.zone_2_source_display.upcast_ref(), ZoneNumber::Two, Command::RequestCurrentSource),
+            (self.audio_format_display.upcast_ref(), ZoneNumber::One, Command::RequestIncomingAudioFormat),
+            (self.audio_channels_display.upcast_ref(), ZoneNumber::One, Command::RequestIncomingAudioFormat),
+            (self.audio_sample_rate_display.upcast_ref(), ZoneNumber::One, Command::RequestIncomingAudioSampleRate),
+        ];
+        for (widget, zone, cc) in displays.iter() {
+            widget.set_visible(self.supports_query(*zone, *cc));
+        }
+        // Each chooser is checked against the command it sends.
+        let choosers: &[(&gtk::Widget, AmpCommand)] = &[
+            (self.brightness_chooser.upcast_ref(), AmpCommand::SetBrightness(Brightness::Level2)),
+            (self.zone_1_power_chooser.upcast_ref(), AmpCommand::SetPower(ZoneNumber::One, PowerState::On)),
+            (self.zone_1_volume_chooser.upcast_ref(), AmpCommand::SetVolume(ZoneNumber::One, Volume::MIN)),
+            (self.zone_1_mute_chooser.upcast_ref(), AmpCommand::SetMute(ZoneNumber::One, MuteState::Muted)),
+            (self.zone_2_power_chooser.upcast_ref(), AmpCommand::SetPower(ZoneNumber::Two, PowerState::On)),
+            (self.zone_2_volume_chooser.upcast_ref(), AmpCommand::SetVolume(ZoneNumber::Two, Volume::MIN)),
+            (self.zone_2_mute_chooser.upcast_ref(), AmpCommand::SetMute(ZoneNumber::Two, MuteState::Muted)),
+        ];
+        for (widget, command) in choosers.iter() {
+            widget.set_visible(can_send(model, command));
+        }
+        self.zone_1_source_chooser.set_visible(can_select_source(model, ZoneNumber::One));
+        self.zone_2_source_chooser.set_visible(can_select_source(model, ZoneNumber::Two));
+        // These are shown only for some sources, see set_source_display.
+        let source_dependent: &[(&gtk::Widget, ZoneNumber, Command)] = &[
+            (self.zone_1_radio_data.upcast_ref(), ZoneNumber::One, Command::RequestDABStation),
+            (self.zone_2_radio_data.upcast_ref(), ZoneNumber::Two, Command::RequestDABStation),
+            (self.video_info.upcast_ref(), ZoneNumber::One, Command::RequestIncomingVideoParameters),
+        ];
+        for (widget, zone, cc) in source_dependent.iter() {
+            if !self.supports_query(*zone, *cc) { widget.hide(); }
+        }
+    }
+
+    /// Can the model of amplifier being controlled answer a query using the command for the zone.
+    fn supports_query(self: &Self, zone: ZoneNumber, cc: Command) -> bool {
+        let model = self.model.get();
+        Request::new(zone, cc, vec![REQUEST_QUERY]).and_then(|request| model.check_request(&request)).is_ok()
+    }
+
     /// Sets the value shown in the connect display UI component.
-    pub fn set_connect_display(self: &Self, connected: ConnectedState) {
-        let string_to_set = connected.to_string();
-        self.connect_display.set_text(&string_to_set);
-        let value: bool = connected.into();
+    ///
+    /// The connect chooser is kept ticked whilst a connection is being made or is made. If the
+    /// connection failed or was lost the connection is dropped, the reason stays on display.
+    /// Whilst a lost connection is not reconnected the displays are marked stale.
+    pub fn set_connect_display(self: &Self, event: &ConnectionEvent) {
+        match event {
+            ConnectionEvent::Connected | ConnectionEvent::Closed => self.set_stale(false),
+            ConnectionEvent::Lost(_) | ConnectionEvent::Reconnecting { .. } => self.set_stale(true),
+            _ => {},
+        }
+        self.connect_display.set_text(&event.to_string());
+        self.connected_state.set(event.into());
+        let value = !event.ends_connection();
         if self.connect_chooser.get_active() != value {
+            self.updating_connect_chooser.set(true);
             self.connect_chooser.set_active(value);
+            self.updating_connect_chooser.set(false);
+        }
+        if let ConnectionEvent::Failed(_) | ConnectionEvent::Lost(_) = event {
+            self.connection.replace(None);
+            self.to_comms_manager.replace(None);
+        }
+    }
+
+    /// Mark the displays of the state of the amplifier as stale, or not, by making them, and
+    /// the choosers that would change the state, insensitive, or not.
+    fn set_stale(self: &Self, stale: bool) {
+        if stale == self.stale.get() { return; }
+        debug!("set_stale:  Setting stale to {}.", stale);
+        self.stale.set(stale);
+        let widgets: [&gtk::Widget; 25] = [
+            self.brightness_display.upcast_ref(), self.brightness_chooser.upcast_ref(),
+            self.zone_1_power_display.upcast_ref(), self.zone_1_power_chooser.upcast_ref(),
+            self.zone_1_volume_display.upcast_ref(), self.zone_1_volume_chooser.upcast_ref(),
+            self.zone_1_mute_display.upcast_ref(), self.zone_1_mute_chooser.upcast_ref(),
+            self.zone_1_source_display.upcast_ref(), self.zone_1_source_chooser.upcast_ref(),
+            self.zone_1_radio_data.upcast_ref(),
+            self.zone_2_power_display.upcast_ref(), self.zone_2_power_chooser.upcast_ref(),
+            self.zone_2_volume_display.upcast_ref(), self.zone_2_volume_chooser.upcast_ref(),
+            self.zone_2_mute_display.upcast_ref(), self.zone_2_mute_chooser.upcast_ref(),
+            self.zone_2_source_display.upcast_ref(), self.zone_2_source_chooser.upcast_ref(),
+            self.zone_2_radio_data.upcast_ref(),
+            self.audio_format_display.upcast_ref(), self.audio_channels_display.upcast_ref(),
+            self.audio_sample_rate_display.upcast_ref(), self.video_info.upcast_ref(),
+            self.device_display.upcast_ref(),
+        ];
+        for widget in widgets.iter() {
+            widget.set_sensitive(!stale);
+        }
+    }
+
+    /// Sets the model and revision shown in the device display UI component.
+    pub fn set_device_display(self: &Self, device: &DeviceInfo) {
+        self.device_display.set_text(&format!("{} {}", device.model, device.revision));
+        self.device_info.replace(Some(device.clone()));
+    }
+
+    /// Record the version of a software component of the amplifier for the about this
+    /// amplifier dialog, replacing any earlier version of the component.
+    pub fn set_software_version(self: &Self, version: SoftwareVersion) {
+        let mut versions = self.software_versions.borrow_mut();
+        match versions.iter_mut().find(|v| v.component == version.component) {
+            Some(v) => *v = version,
+            None => versions.push(version),
         }
     }
 
@@ -443,16 +704,15 @@ impl ControlWindow {
     }
 
     /// Sets the value shown in the zone specific volume display UI component.
-    pub fn set_volume_display(self: &Self, zone: ZoneNumber, volume: u8) {
-        assert!(volume < 100);
+    pub fn set_volume_display(self: &Self, zone: ZoneNumber, volume: Volume) {
         let (volume_display, volume_chooser) = match zone {
             ZoneNumber::One => (&self.zone_1_volume_display, &self.zone_1_volume_chooser),
             ZoneNumber::Two => (&self.zone_2_volume_display, &self.zone_2_volume_chooser),
         };
         let text = volume.to_string();
         volume_display.set_text(&text);
-        if volume_chooser.get_value() as u8 != volume {
-            volume_chooser.set_value(volume as f64);
+        if volume_chooser.get_value() as u8 != volume.level() {
+            volume_chooser.set_value(volume.level() as f64);
         }
     }
 
@@ -478,8 +738,13 @@ impl ControlWindow {
         };
         let source_id = source.to_string();
         source_display.set_text(&source_id);
-        if source == Source::TUNER { radio_data.show(); }
+        if source == Source::TUNER && self.supports_query(zone, Command::RequestDABStation) { radio_data.show(); }
         else { radio_data.hide(); }
+        // The now decoding displays are for zone 1 only, and only HDMI sources have video.
+        if zone == ZoneNumber::One {
+            if source.video_source().is_some() && self.supports_query(zone, Command::RequestIncomingVideoParameters) { self.video_info.show(); }
+            else { self.clear_video_parameters_display(); }
+        }
         match source_chooser.get_active_id() {
             Some(id) => if id != source_id { source_chooser.set_active_id(Some(&source_id)); },
             None => { source_chooser.set_active_id(Some(&source_id)); },
@@ -516,9 +781,83 @@ impl ControlWindow {
         radio_data.show();
     }
 
+    /// Sets the values shown in the now decoding audio format and channels display UI components.
+    pub fn set_audio_format_display(self: &Self, format: AudioFormat, channels: ChannelConfiguration) {
+        self.audio_format_display.set_text(&format.to_string());
+        self.audio_channels_display.set_text(&channels.to_string());
+    }
+
+    /// Sets the value shown in the now decoding sample rate display UI component.
+    pub fn set_audio_sample_rate_display(self: &Self, sample_rate: SampleRate) {
+        self.audio_sample_rate_display.set_text(&sample_rate.to_string());
+    }
+
+    /// Sets the values shown in the now decoding video display UI components. Nothing is shown
+    /// whilst the video displays are hidden as the source has no video.
+    pub fn set_video_parameters_display(self: &Self, parameters: VideoParameters) {
+        if !self.video_info.get_visible() { return; }
+        self.video_resolution_display.set_text(&parameters.resolution());
+        if parameters.is_present() {
+            self.video_aspect_ratio_display.set_text(&parameters.aspect_ratio.to_string());
+            self.video_colourspace_display.set_text(&parameters.colourspace.to_string());
+        } else {
+            self.video_aspect_ratio_display.set_text("");
+            self.video_colourspace_display.set_text("");
+        }
+    }
+
+    /// Clear and hide the now decoding video display UI components.
+    fn clear_video_parameters_display(self: &Self) {
+        self.video_resolution_display.set_text("");
+        self.video_aspect_ratio_display.set_text("");
+        self.video_colourspace_display.set_text("");
+        self.video_info.hide();
+    }
+
+    /// Clear the values shown in the now decoding display UI components.
+    fn clear_now_decoding_display(self: &Self) {
+        self.audio_format_display.set_text("");
+        self.audio_channels_display.set_text("");
+        self.audio_sample_rate_display.set_text("");
+        self.clear_video_parameters_display();
+    }
+
+    /// Show a message about an error answer from the amplifier. The displays of the state of
+    /// the amplifier are left as they are.
+    pub fn show_answer_error(self: &Self, error: &AnswerError) {
+        let power_display = match error.zone {
+            ZoneNumber::One => &self.zone_1_power_display,
+            ZoneNumber::Two => &self.zone_2_power_display,
+        };
+        let in_standby = PowerState::from_str(power_display.get_text().as_str()) == Ok(PowerState::Standby);
+        let message = if error.answer == ErrorAnswerCode::CommandInvalidAtThisTime && in_standby {
+            format!("Zone {} cannot {} while in standby.", error.zone as u8, error.action())
+        } else {
+            error.to_string()
+        };
+        self.answer_error_display.set_text(&message);
+        self.answer_error_bar.show();
+    }
+
     /// Accessor for the current value of the connect display UI component.
     pub fn get_connect_display_value(self: &Self) -> ConnectedState {
-        self.connect_display.get_text().as_str().into()
+        self.connected_state.get()
+    }
+
+    /// Accessor for whether the displays are marked as stale, showing the state of the
+    /// amplifier before the connection was lost.
+    pub fn is_stale(self: &Self) -> bool {
+        self.stale.get()
+    }
+
+    /// Accessor for the current value of the device display UI component.
+    pub fn get_device_display_value(self: &Self) -> String {
+        self.device_display.get_text().to_string()
+    }
+
+    /// Accessor for the software versions reported by the amplifier.
+    pub fn get_software_versions(self: &Self) -> Vec<SoftwareVersion> {
+        self.software_versions.borrow().clone()
     }
 
     /// Accessor for the current value of the brightness display UI component.
@@ -535,13 +874,13 @@ impl ControlWindow {
     }
 
     /// Accessor for the current value of the zone specific volume display UI component.
-   pub fn get_volume_display_value(self: &Self, zone: ZoneNumber) -> u8 {
+   pub fn get_volume_display_value(self: &Self, zone: ZoneNumber) -> Volume {
         match match zone {
             ZoneNumber::One => self.zone_1_volume_display.get_text(),
             ZoneNumber::Two => self.zone_2_volume_display.get_text(),
         }.parse::<u8>() {
-            Ok(v) => v,
-            Err(_) => 0u8,
+            Ok(v) => Volume::new(v).unwrap_or_default(),
+            Err(_) => Volume::default(),
         }
     }
 
@@ -561,6 +900,43 @@ impl ControlWindow {
         }.get_text().as_str()).unwrap()
     }
 
+    /// Accessor for the current value of the now decoding audio format display UI component.
+    pub fn get_audio_format_display_value(self: &Self) -> String {
+        self.audio_format_display.get_text().to_string()
+    }
+
+    /// Accessor for the current value of the now decoding sample rate display UI component.
+    pub fn get_audio_sample_rate_display_value(self: &Self) -> String {
+        self.audio_sample_rate_display.get_text().to_string()
+    }
+
+    /// Accessor for the current value of the now decoding video resolution display UI component.
+    pub fn get_video_resolution_display_value(self: &Self) -> String {
+        self.video_resolution_display.get_text().to_string()
+    }
+
+    /// Accessor for the message about the last error answer from the amplifier, if it is
+    /// being shown.
+    pub fn get_answer_error_display_value(self: &Self) -> Option<String> {
+        if self.answer_error_bar.get_visible() { Some(self.answer_error_display.get_text().to_string()) }
+        else { None }
+    }
+
+    /// Accessor for the sender of data to the comms manager, if there is a connection.
+    pub fn try_get_to_comms_manager(self: &Self) -> Option<AmpSender> {
+        self.to_comms_manager.borrow().clone()
+    }
+
+    /// Accessor for the channel to the comms manager for polling the state of the amplifier,
+    /// this has a lower priority than the channel used for user actions but shares its record of
+    /// requests awaiting an answer.
+    pub fn try_get_polling_sender(self: &Self) -> Option<AmpSender> {
+        match (self.to_comms_manager.borrow().as_ref(), self.connection.borrow().as_ref()) {
+            (Some(sender), Some(connection)) => Some(sender.sharing_pending_requests(connection.polling_sender())),
+            _ => None,
+        }
+    }
+
     /// Accessor for whether the client is connected to an amplifier – real or mock.
     ///
     /// In non-test situation, if there is no connection, a message dialogue is displayed.
@@ -586,7 +962,7 @@ impl ControlWindow {
     // overall abstraction.
 
     #[doc(hidden)]
-    pub fn get_to_comms_manager_field(self: &Self) -> &RefCell<Option<futures::channel::mpsc::Sender<Vec<u8>>>> {
+    pub fn get_to_comms_manager_field(self: &Self) -> &RefCell<Option<AmpSender>> {
         &self.to_comms_manager
     }
 
@@ -638,3 +1014,29 @@ impl ControlWindow {
     }
 
 }
+
+/// Can the model of amplifier carry out a command sent by a chooser.
+fn can_send(model: Model, command: &AmpCommand) -> bool {
+    model.to_requests(command).is_ok()
+}
+
+/// Can the model of amplifier select any of its sources for the zone.
+fn can_select_source(model: Model, zone: ZoneNumber) -> bool {
+    model.sources().iter().any(|source| can_send(model, &AmpCommand::SelectSource(zone, *source)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avr850_keeps_the_zone_choosers() {
+        for zone in [ZoneNumber::One, ZoneNumber::Two].iter() {
+            assert!(can_send(Model::AVR850, &AmpCommand::SetPower(*zone, PowerState::On)));
+            assert!(can_send(Model::AVR850, &AmpCommand::SetVolume(*zone, Volume::MIN)));
+            assert!(can_send(Model::AVR850, &AmpCommand::SetMute(*zone, MuteState::Muted)));
+            assert!(can_select_source(Model::AVR850, *zone));
+        }
+        assert!(can_send(Model::AVR850, &AmpCommand::SetBrightness(Brightness::Level2)));
+    }
+}
@@ -19,7 +19,7 @@
 
 //! This module provides various functions to be used from the UI code in the
 //! [control_window](../control_window/index.html) module to send data (in the form of Arcam
-//! protocol packets, see [arcam_protocol](../arcam_protocol/index.html) module) to the
+//! protocol packets, see the [arcam_protocol](../../arcam_protocol/index.html) crate) to the
 //! [comms_manager](../comms_manager/index.html) module functions for forwarding to the
 //! amplifier, and functions to be called by functions in the
 //! [comms_manager](../comms_manager/index.html) module to transform bytes received from the
@@ -31,7 +31,13 @@
 //! ([comms_manager](../comms_manager/index.html) module). This is not Mediator or Façade in the
 //! [Gang of Four](https://en.wikipedia.org/wiki/Design_Patterns) design patterns sense as that
 //! is all about class structures in an object oriented system.
+//!
+//! For code other than the UI there is a [Client](struct.Client.html) whose queries of the
+//! amplifier can be awaited.
 
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::fmt;
 use std::rc::Rc;
 use std::time::Duration;
 
@@ -39,285 +45,515 @@ use gtk;
 use gtk::prelude::*;
 
 use futures::channel::mpsc::Sender;
+use futures::channel::oneshot;
+use futures::future::{self, Either};
 
-use log::debug;
-
-#[allow(unused_imports)]  // Compiler misses the use in a derive.
-use num_derive::FromPrimitive;
-use num_traits::FromPrimitive;
+use log::{debug, warn};
 
-use crate::arcam_protocol::{
-    Command, MuteState, PowerState, RC5Command, Request, Response, Source, ZoneNumber,
-    REQUEST_QUERY,
-    get_rc5command_data
+use arcam_protocol::amp_model::Model;
+use arcam_protocol::{
+    AmpCommand, AmpEvent, AnswerError, Command, DeviceInfo, Frame, MuteState, PacketDecoder, PendingRequests, PowerState,
+    ProtocolError, Request, Response, SoftwareComponent, Source, Volume, ZoneNumber,
+    create_amx_request,
 };
-use crate::comms_manager;
-use crate::control_window::{ControlWindow, ConnectedState};
+
+use crate::comms_manager::{self, Connection, ConnectionEvent, ConnectionSettings, Heartbeat};
+use crate::control_window::ControlWindow;
 
 //pub type RequestTuple = (ZoneNumber, Command, Vec<u8>);
 //pub type ResponseTuple = (ZoneNumber, Command, AnswerCode, Vec<u8>);
 
+/// The channel to the comms manager for sending
+/// [Request](../../arcam_protocol/struct.Request.html)s to the amplifier, together with the
+/// requests sent over the connection that the amplifier has yet to answer, so that an error
+/// answer can be tied back to the request that caused it.
+///
+/// Clones share the record of requests, as do the senders made by
+/// [sharing_pending_requests](struct.AmpSender.html#method.sharing_pending_requests), so all the
+/// senders for a connection must come from the same one.
+#[derive(Clone, Debug)]
+pub struct AmpSender {
+    sender: Sender<Vec<u8>>,
+    pending_requests: Rc<RefCell<PendingRequests>>,
+}
+
+impl AmpSender {
+    /// A sender on `sender` with no requests awaiting an answer.
+    pub fn new(sender: Sender<Vec<u8>>) -> Self {
+        Self { sender, pending_requests: Rc::new(RefCell::new(PendingRequests::new())) }
+    }
+
+    /// A sender on `sender`, another channel of the same connection, sharing the record of
+    /// requests awaiting an answer with this one.
+    pub fn sharing_pending_requests(self: &Self, sender: Sender<Vec<u8>>) -> Self {
+        Self { sender, pending_requests: self.pending_requests.clone() }
+    }
+
+    /// Is the channel to the comms manager closed.
+    pub fn is_closed(self: &Self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Tie a [Response](../../arcam_protocol/struct.Response.html) back to the request sent on
+    /// this connection that it answers.
+    pub fn answer(self: &Self, response: &Response) -> Result<Option<Request>, AnswerError> {
+        self.pending_requests.borrow_mut().answer(response)
+    }
+
+    /// Forget the requests awaiting an answer, e.g. when the connection is lost.
+    pub fn forget_requests(self: &Self) {
+        self.pending_requests.borrow_mut().clear();
+    }
+}
+
+/// A query made via a [Client](struct.Client.html) awaiting the
+/// [Response](../../arcam_protocol/struct.Response.html) that answers one of the requests it
+/// sent.
+#[derive(Debug)]
+struct Query {
+    requests: Vec<Request>,
+    answer: oneshot::Sender<Result<Response, AnswerError>>,
+}
+
+/// How long a [Client](struct.Client.html) waits for the amplifier to answer a query, and how
+/// many times it asks again if there is no answer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct QuerySettings {
+    pub timeout: Duration,
+    pub retries: u32,
+}
+
+impl Default for QuerySettings {
+    /// The protocol specification says the amplifier answers within three seconds.
+    fn default() -> Self {
+        Self { timeout: Duration::from_secs(3), retries: 2 }
+    }
+}
+
+/// The reasons a query made via a [Client](struct.Client.html) does not get a
+/// [Response](../../arcam_protocol/struct.Response.html).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum QueryError {
+    /// The query cannot be encoded or the model of amplifier cannot handle it.
+    Protocol(ProtocolError),
+    /// The amplifier answered with an error.
+    Answer(AnswerError),
+    /// The amplifier did not answer any of the attempts.
+    NoAnswer { attempts: u32 },
+    /// The connection to the amplifier closed before the amplifier answered.
+    NotConnected,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Protocol(error) => write!(f, "{}", error),
+            Self::Answer(error) => write!(f, "{}", error),
+            Self::NoAnswer { attempts } => write!(f, "No answer from the amplifier after {} attempts.", attempts),
+            Self::NotConnected => write!(f, "Not connected to the amplifier."),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// A way for code other than the UI to ask questions of the amplifier and await the answers.
+///
+/// The amplifier answers within three seconds but not necessarily in the order asked, so each
+/// query awaits the [Response](../../arcam_protocol/struct.Response.html) that the record of
+/// requests awaiting an answer ties back to the request the query sent. A query with no answer
+/// within the timeout is sent again, up to the number of retries given by the
+/// [QuerySettings](struct.QuerySettings.html).
+///
+/// All bytes received from the amplifier must be passed to
+/// [handle_response_data](struct.Client.html#method.handle_response_data). A client cannot be
+/// used on a connection of the UI: the UI passes the bytes it receives to
+/// [handle_response_data](fn.handle_response_data.html), which does not answer queries, so
+/// every query would end with [QueryError::NoAnswer](enum.QueryError.html#variant.NoAnswer).
+#[derive(Clone, Debug)]
+pub struct Client {
+    sender: AmpSender,
+    model: Model,
+    settings: QuerySettings,
+    queries: Rc<RefCell<Vec<Query>>>,
+}
+
+impl Client {
+    /// A client sending its requests on `sender`, which should be the sender of a
+    /// [Connection](../comms_manager/struct.Connection.html) to an amplifier of the given model.
+    pub fn new(sender: Sender<Vec<u8>>, model: Model, settings: QuerySettings) -> Self {
+        Self { sender: AmpSender::new(sender), model, settings, queries: Rc::new(RefCell::new(Vec::new())) }
+    }
+
+    /// Ask the amplifier for the state of `cc` in `zone`, returning the answer.
+    pub async fn query(self: &Self, zone: ZoneNumber, cc: Command) -> Result<Response, QueryError> {
+        let requests = self.model.to_requests(&AmpCommand::Query(zone, cc)).map_err(QueryError::Protocol)?;
+        let (answer, mut answered) = oneshot::channel();
+        self.queries.borrow_mut().push(Query { requests: requests.clone(), answer });
+        let mut sender = self.sender.clone();
+        let attempts = self.settings.retries + 1;
+        for attempt in 1..=attempts {
+            if sender.is_closed() { return Err(QueryError::NotConnected); }
+            for request in requests.iter() {
+                send_request(&mut sender, self.model, request).map_err(QueryError::Protocol)?;
+            }
+            match future::select(&mut answered, glib::timeout_future(self.settings.timeout)).await {
+                Either::Left((Ok(answer), _)) => return answer.map_err(QueryError::Answer),
+                Either::Left((Err(_), _)) => return Err(QueryError::NotConnected),
+                Either::Right(_) => {
+                    debug!("query:  No answer to attempt {} of {} for {:?} {:?}.", attempt, attempts, zone, cc);
+                    let mut pending = self.sender.pending_requests.borrow_mut();
+                    for request in requests.iter() { pending.forget(request); }
+                },
+            }
+        }
+        Err(QueryError::NoAnswer { attempts })
+    }
+
+    /// Deal with bytes received from the amplifier when the UI is not running: each complete
+    /// [Response](../../arcam_protocol/struct.Response.html) answers any query awaiting it.
+    pub fn handle_response_data(self: &Self, decoder: &mut PacketDecoder<Response>, data: &[u8]) {
+        debug!("Client::handle_response_data:  Got data {:?}.", data);
+        decoder.set_dialect(self.model.dialect());
+        decoder.push(data);
+        for frame in decoder {
+            match frame {
+                Frame::Packet(response) => self.answer_query(&response),
+                x => debug!("Client::handle_response_data:  Ignoring {:?}.", x),
+            }
+        }
+    }
+
+    /// Tie a [Response](../../arcam_protocol/struct.Response.html) back to the request it
+    /// answers, passing it on to the query that sent that request, if any.
+    fn answer_query(self: &Self, response: &Response) {
+        let answer = self.sender.answer(response);
+        let request = match &answer {
+            Ok(request) => request.as_ref(),
+            Err(error) => error.request.as_ref(),
+        };
+        let mut queries = self.queries.borrow_mut();
+        // Queries that have given up have dropped their receiver.
+        queries.retain(|query| !query.answer.is_canceled());
+        let index = request.and_then(|request| queries.iter().position(|query| query.requests.contains(request)));
+        if let Some(index) = index {
+            let _ = queries.remove(index).answer.send(answer.map(|_| response.clone()));
+        }
+    }
+}
+
 /// Connect to an Arcam amp at the address given.
 pub fn connect_to_amp(
     to_control_window: &glib::Sender<Vec<u8>>,
+    connection_events: &glib::Sender<ConnectionEvent>,
     address: &str,
-    port_number: u16
-) -> Result<futures::channel::mpsc::Sender<Vec<u8>>, String> {
+    port_number: u16,
+    settings: ConnectionSettings,
+) -> Result<Connection, String> {
     debug!("connect_to_amp:  Connecting to {}:{}.", address, port_number);
-    let x = comms_manager::connect_to_amp(to_control_window, address, port_number);
+    let x = comms_manager::connect_to_amp(to_control_window, connection_events, address, port_number, settings);
     match &x {
-        Ok(y) => debug!("connect_to_amp:  Got Ok result {:p}.", y),
+        Ok(y) => debug!("connect_to_amp:  Got Ok result {:?}.", y),
         Err(e) => debug!("connect_to_amp:  Got Err result – {:?}.", e),
     }
     x
 }
 
-/// Terminate the current connection.
-pub fn disconnect_from_amp() {
-    // TODO What to do to disconnect from the amp?
+/// The [Heartbeat](../comms_manager/struct.Heartbeat.html) for a connection to a model of
+/// amplifier. Models without the `Heartbeat` command, e.g. the AVR600, are asked for the power
+/// state of zone 1 instead, a query that is cheap to answer.
+pub fn heartbeat(model: Model) -> Option<Heartbeat> {
+    let requests = model.to_requests(&AmpCommand::Query(ZoneNumber::One, Command::Heartbeat))
+        .or_else(|_| model.to_requests(&AmpCommand::Query(ZoneNumber::One, Command::Power)));
+    match requests {
+        Ok(requests) => Some(Heartbeat::new(requests.iter().flat_map(Request::to_bytes).collect())),
+        Err(e) => {
+            debug!("heartbeat:  No heartbeat for {} – {}.", model, e);
+            None
+        },
+    }
+}
+
+/// Terminate a connection. The requests awaiting an answer go with the
+/// [AmpSender](struct.AmpSender.html)s of the connection.
+pub fn disconnect_from_amp(connection: Connection) {
+    comms_manager::disconnect_from_amp(connection);
+}
+
+/// Deal with a [ConnectionEvent](../comms_manager/enum.ConnectionEvent.html) from the comms
+/// manager.
+///
+/// The connect display of the UI shows the state of the connection. Once connected, or
+/// reconnected, the amplifier is asked to identify itself and the state of the amplifier is
+/// requested so as to initialise all the displays of the UI.
+pub fn handle_connection_event(control_window: &Rc<ControlWindow>, event: &ConnectionEvent) {
+    debug!("handle_connection_event:  Got {:?}.", event);
+    match event {
+        ConnectionEvent::Connected => {
+            if let Some(mut sender) = control_window.try_get_polling_sender() {
+                send_request_bytes(&mut sender, &create_amx_request());
+                initialise_control_window(&mut sender, control_window.get_model());
+            }
+        },
+        ConnectionEvent::Failed(_) | ConnectionEvent::Lost(_) | ConnectionEvent::Reconnecting { .. } | ConnectionEvent::Closed => {
+            if let Some(sender) = control_window.try_get_to_comms_manager() { sender.forget_requests(); }
+        },
+        _ => {},
+    }
+    control_window.set_connect_display(event);
 }
 
 /// Send a sequence of bytes to the comms manager (via the appropriate channel) for forwarding
 /// to the amplifier.
-pub fn send_request_bytes(sender: &mut Sender<Vec<u8>>, request: &Vec<u8>) {
+pub fn send_request_bytes(sender: &mut AmpSender, request: &Vec<u8>) {
     debug!("send_request_bytes:  Send message to amp {:?}.", request);
-    match sender.try_send(request.to_vec()) {
+    match sender.sender.try_send(request.to_vec()) {
         Ok(_) => {},
         Err(e) => debug!("send_request_bytes:  Failed to send packet – {:?}.", e),
     }
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to the comms manager (via the
-/// appropriate channel) for forwarding to the amplifier.
-pub fn send_request(sender: &mut Sender<Vec<u8>>, request: &Request) {
-    debug!("send_request:  Send message to amp {:?}.", request);
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to the comms manager (via the
+/// appropriate channel) for forwarding to the amplifier, recording it as awaiting an answer.
+///
+/// Nothing is sent if the [Model](../../arcam_protocol/amp_model/enum.Model.html) of amplifier
+/// cannot handle the request or the request does not apply to its zone.
+pub fn send_request(sender: &mut AmpSender, model: Model, request: &Request) -> Result<(), ProtocolError> {
+    debug!("send_request:  Send message to {} {:?}.", model, request);
+    model.check_request(request)?;
+    sender.pending_requests.borrow_mut().push(request.clone());
     send_request_bytes(sender, &request.to_bytes());
+    Ok(())
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to respond with the state of the
+/// Send the [Request](../../arcam_protocol/struct.Request.html)s that carry out an
+/// [AmpCommand](../../arcam_protocol/enum.AmpCommand.html) to the comms manager (via the
+/// appropriate channel) for forwarding to the amplifier.
+///
+/// Nothing is sent if the command cannot be encoded or the
+/// [Model](../../arcam_protocol/amp_model/enum.Model.html) of amplifier cannot handle it.
+pub fn send_command(sender: &mut AmpSender, model: Model, command: &AmpCommand) -> Result<(), ProtocolError> {
+    debug!("send_command:  Send command to {} {:?}.", model, command);
+    for request in model.to_requests(command)?.iter() {
+        send_request(sender, model, request)?;
+    }
+    Ok(())
+}
+
+/// Send an [AmpCommand](../../arcam_protocol/enum.AmpCommand.html) logging rather than returning
+/// any error.
+fn send_command_logging_error(sender: &mut AmpSender, model: Model, command: &AmpCommand) {
+    if let Err(e) = send_command(sender, model, command) {
+        debug!("send_command_logging_error:  Failed to send command {:?} – {}.", command, e);
+    }
+}
+
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to respond with the state of the
 /// brightness to the amplifier.
-pub fn get_brightness_from_amp(sender: &mut Sender<Vec<u8>>) {
-    send_request(sender, &Request::new(ZoneNumber::One, Command::DisplayBrightness, vec![REQUEST_QUERY]).unwrap());
+pub fn get_brightness_from_amp(sender: &mut AmpSender, model: Model) {
+    send_command_logging_error(sender, model, &AmpCommand::Query(ZoneNumber::One, Command::DisplayBrightness));
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to respond with the current power
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to respond with the current power
 /// state for the given zone to the amplifier.
-pub fn get_power_from_amp(sender: &mut Sender<Vec<u8>>, zone: ZoneNumber) {
-    send_request(sender, &Request::new(zone, Command::Power, vec![REQUEST_QUERY]).unwrap());
+pub fn get_power_from_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber) {
+    send_command_logging_error(sender, model, &AmpCommand::Query(zone, Command::Power));
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to amend the power state of a given
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to amend the power state of a given
 /// zone to the amplifier.
-pub fn set_power_on_amp(sender: &mut Sender<Vec<u8>>, zone: ZoneNumber, power: PowerState) {
-    let rc5_data = get_rc5command_data(
-        if zone == ZoneNumber::One {
-            if power == PowerState::On { RC5Command::PowerOn } else { RC5Command::PowerOff }
-        } else {
-            if power == PowerState::On { RC5Command::Zone2PowerOn } else { RC5Command::Zone2PowerOff }
-        }
-    );
-    send_request(sender, &Request::new(zone, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1]).unwrap());
-    // SimulateRC5IRCommand commands do not respond with the changed status.
-    get_power_from_amp(sender, zone);
+pub fn set_power_on_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber, power: PowerState) {
+    send_command_logging_error(sender, model, &AmpCommand::SetPower(zone, power));
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to respond with the volume for the
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to respond with the volume for the
 /// given zone to the amplifier.
-pub fn get_volume_from_amp(sender: &mut Sender<Vec<u8>>, zone: ZoneNumber) {
-    send_request(sender, &Request::new(zone, Command::SetRequestVolume, vec![REQUEST_QUERY]).unwrap());
+pub fn get_volume_from_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber) {
+    send_command_logging_error(sender, model, &AmpCommand::Query(zone, Command::SetRequestVolume));
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to amend the volume of a given zone
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to amend the volume of a given zone
 /// to the amplifier.
-pub fn set_volume_on_amp(sender: &mut Sender<Vec<u8>>, zone:ZoneNumber, volume: u8) {
-    assert!(volume < 100);
-    send_request(sender, &Request::new(zone, Command::SetRequestVolume, vec![volume]).unwrap());
+pub fn set_volume_on_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber, volume: Volume) {
+    send_command_logging_error(sender, model, &AmpCommand::SetVolume(zone, volume));
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to respond with the mute state for
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to respond with the mute state for
 /// the given zone to the amplifier.
-pub fn get_mute_from_amp(sender: &mut Sender<Vec<u8>>, zone: ZoneNumber) {
-    send_request(sender, &Request::new(zone, Command::RequestMuteStatus, vec![REQUEST_QUERY]).unwrap());
+pub fn get_mute_from_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber) {
+    send_command_logging_error(sender, model, &AmpCommand::Query(zone, Command::RequestMuteStatus));
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to amend the mute state of a given
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to amend the mute state of a given
 /// zone to the amplifier.
-pub fn set_mute_on_amp(sender: &mut Sender<Vec<u8>>, zone: ZoneNumber, mute: MuteState) {
-    let rc5_data = get_rc5command_data(
-        if zone == ZoneNumber::One {
-            if mute == MuteState::Muted { RC5Command::MuteOn } else { RC5Command::MuteOff }
-        } else {
-            if mute == MuteState::Muted { RC5Command::Zone2MuteOn } else { RC5Command::Zone2MuteOff }
-        }
-    );
-    send_request(sender, &Request::new(zone, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1]).unwrap());
-    // SimulateRC5IRCommand commands do not respond with the changed status.
-    get_mute_from_amp(sender, zone);
+pub fn set_mute_on_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber, mute: MuteState) {
+    send_command_logging_error(sender, model, &AmpCommand::SetMute(zone, mute));
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to respond with the source for the
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to respond with the source for the
 /// given zone to the amplifier.
-pub fn get_source_from_amp(sender: &mut Sender<Vec<u8>>, zone: ZoneNumber) {
-    send_request(sender, &Request::new(zone, Command::RequestCurrentSource, vec![REQUEST_QUERY]).unwrap());
+pub fn get_source_from_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber) {
+    send_command_logging_error(sender, model, &AmpCommand::Query(zone, Command::RequestCurrentSource));
 }
 
-/// Send a [Request](../arcam_protocol/struct.Request.html) to amend the source of a given zone
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to amend the source of a given zone
 /// to the amplifier.
-pub fn set_source_on_amp(sender: &mut Sender<Vec<u8>>, zone: ZoneNumber, source: Source) {
-    let rc5_command = match source {
-        Source::FollowZone1 => {
-            assert_eq!(zone, ZoneNumber::Two);
-            RC5Command::SetZone2ToFollowZone1
-        },
-        Source::CD => RC5Command::CD,
-        Source::BD => RC5Command::BD,
-        Source::AV => RC5Command::AV,
-        Source::SAT => RC5Command::Sat,
-        Source::PVR => RC5Command::PVR,
-        Source::VCR => RC5Command::VCR,
-        Source::AUX => RC5Command::Aux,
-        Source::DISPLAY => RC5Command::Display,
-        Source::TUNER => RC5Command::Radio,
-        Source::TUNERDAB => RC5Command::Radio,
-        Source::NET => RC5Command::Net,
-        Source::USB => RC5Command::USB,
-        Source::STB => RC5Command::STB,
-        Source::GAME => RC5Command::Game,
-    };
-    let rc5_data = get_rc5command_data(rc5_command);
-    send_request(sender, &Request::new(zone, Command::SimulateRC5IRCommand, vec![rc5_data.0, rc5_data.1]).unwrap());
-    // SimulateRC5IRCommand commands do not respond with the changed status.
-    get_source_from_amp(sender, zone);
-}
-
-/// Send [Request](../arcam_protocol/struct.Request.html)s to the amplifier so as to get
-/// [Response](../arcam_protocol/struct.Response.html)s from the amplifier so as to set all the
-/// displays of the UI.
-// Experimental evidence indicates that a real AVR 850 cannot deal with a large number
-// of requests being sent to it at once. This means requests must be sent with a small
-// time gap. The gap has been ascertained by rough experiment with an AVR850 rather
-// than guesswork: 150 ms seems insufficient, 175 ms works sometimes, 200 ms seems
-// mostly to work but not always, 225 ms seems to work always.
-pub fn initialise_control_window(sender: &mut Sender<Vec<u8>>) {
-    glib::timeout_add_local(Duration::from_millis(225), {
+pub fn set_source_on_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber, source: Source) {
+    send_command_logging_error(sender, model, &AmpCommand::SelectSource(zone, source));
+}
+
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to respond with the version of a
+/// software component to the amplifier.
+pub fn get_software_version_from_amp(sender: &mut AmpSender, model: Model, component: SoftwareComponent) {
+    send_command_logging_error(sender, model, &AmpCommand::QuerySoftwareVersion(component));
+}
+
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to respond with the incoming audio
+/// format for the given zone to the amplifier.
+pub fn get_audio_format_from_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber) {
+    send_command_logging_error(sender, model, &AmpCommand::Query(zone, Command::RequestIncomingAudioFormat));
+}
+
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to respond with the incoming audio
+/// sample rate for the given zone to the amplifier.
+pub fn get_audio_sample_rate_from_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber) {
+    send_command_logging_error(sender, model, &AmpCommand::Query(zone, Command::RequestIncomingAudioSampleRate));
+}
+
+/// Send a [Request](../../arcam_protocol/struct.Request.html) to respond with the incoming video
+/// parameters for the given zone to the amplifier.
+pub fn get_video_parameters_from_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber) {
+    send_command_logging_error(sender, model, &AmpCommand::Query(zone, Command::RequestIncomingVideoParameters));
+}
+
+/// Send [Request](../../arcam_protocol/struct.Request.html)s to the amplifier so as to get
+/// [Response](../../arcam_protocol/struct.Response.html)s from the amplifier so as to set the now
+/// decoding displays of the UI. The video parameters are only asked for if `video` is true.
+// The amplifier takes a while to recognise the incoming stream after a change of source, so
+// the requests are not sent immediately.
+pub fn get_now_decoding_from_amp(sender: &mut AmpSender, model: Model, zone: ZoneNumber, video: bool) {
+    glib::timeout_add_local(Duration::from_millis(500), {
         let mut s = sender.clone();
-        let mut count = -1;
         move || {
-            count += 1;
-            match count {
-                0 => { get_brightness_from_amp(&mut s); Continue(true) },
-                1 => { get_power_from_amp(&mut s, ZoneNumber::One); Continue(true) },
-                2=> { get_power_from_amp(&mut s, ZoneNumber::Two); Continue(true) },
-                3 => { get_volume_from_amp(&mut s, ZoneNumber::One); Continue(true) },
-                4 => { get_volume_from_amp(&mut s, ZoneNumber::Two); Continue(true) },
-                5 => { get_mute_from_amp(&mut s, ZoneNumber::One); Continue(true) },
-                6 => { get_mute_from_amp(&mut s, ZoneNumber::Two); Continue(true) },
-                7 => { get_source_from_amp(&mut s, ZoneNumber::One); Continue(true) },
-                8 => { get_source_from_amp(&mut s, ZoneNumber::Two); Continue(false) },
-                _ => Continue(false),
-            }
+            get_audio_format_from_amp(&mut s, model, zone);
+            get_audio_sample_rate_from_amp(&mut s, model, zone);
+            if video { get_video_parameters_from_amp(&mut s, model, zone); }
+            Continue(false)
         }
     });
 }
 
-/// Deal with a [Response](../arcam_protocol/struct.Response.html) packet received from the
+/// Send [Request](../../arcam_protocol/struct.Request.html)s to the amplifier so as to get
+/// [Response](../../arcam_protocol/struct.Response.html)s from the amplifier so as to set all the
+/// displays of the UI, and the software versions for the about this amplifier dialog.
+///
+/// The requests are all sent at once, the comms manager spaces them out as the amplifier
+/// requires.
+pub fn initialise_control_window(sender: &mut AmpSender, model: Model) {
+    get_brightness_from_amp(sender, model);
+    for zone in ZoneNumber::ALL.iter() {
+        get_power_from_amp(sender, model, *zone);
+    }
+    for zone in ZoneNumber::ALL.iter() {
+        get_volume_from_amp(sender, model, *zone);
+    }
+    for zone in ZoneNumber::ALL.iter() {
+        get_mute_from_amp(sender, model, *zone);
+    }
+    for zone in ZoneNumber::ALL.iter() {
+        get_source_from_amp(sender, model, *zone);
+    }
+    get_audio_format_from_amp(sender, model, ZoneNumber::One);
+    get_audio_sample_rate_from_amp(sender, model, ZoneNumber::One);
+    get_video_parameters_from_amp(sender, model, ZoneNumber::One);
+    for component in SoftwareComponent::ALL.iter() {
+        get_software_version_from_amp(sender, model, *component);
+    }
+}
+
+/// Deal with a [Response](../../arcam_protocol/struct.Response.html) packet received from the
 /// amplifier.
 ///
-/// This function transforms [Response](../arcam_protocol/struct.Response.html)s into actions on
-/// the UI.
+/// This function transforms [Response](../../arcam_protocol/struct.Response.html)s into
+/// [AmpEvent](../../arcam_protocol/enum.AmpEvent.html)s and then into actions on the UI.
 fn handle_response(control_window: &Rc<ControlWindow>, response: &Response) {
     debug!("handle_response:  Dealing with response {:?}.", response);
-    match response.cc {
-        Command::Power => {
-            assert_eq!(response.data.len(), 1);
-            control_window.set_power_display(response.zone, FromPrimitive::from_u8(response.data[0]).unwrap());
+    let answer = match control_window.try_get_to_comms_manager() {
+        Some(sender) => sender.answer(response),
+        // With no connection there are no requests awaiting an answer.
+        None => PendingRequests::new().answer(response),
+    };
+    match answer {
+        Ok(_) => match AmpEvent::try_from(response) {
+            Ok(event) => handle_event(control_window, &event),
+            Err(e) => debug!("handle_response:  Failed to decode response {:?} – {}.", response, e),
         },
-        Command::DisplayBrightness => {
-            assert_eq!(response.data.len(), 1);
-            control_window.set_brightness_display(FromPrimitive::from_u8(response.data[0]).unwrap())
+        Err(error) => {
+            // A failed query leaves nothing for the user to act on, but should not happen.
+            if error.request.as_ref().map_or(false, Request::is_query) {
+                warn!("handle_response:  Got error answer to query {:?} – {}.", error.request, error);
+            } else {
+                debug!("handle_response:  Got error answer to {:?} – {}.", error.request, error);
+                control_window.show_answer_error(&error);
+            }
         },
-        Command::SetRequestVolume => {
-            assert_eq!(response.data.len(), 1);
-            control_window.set_volume_display(response.zone, response.data[0]);
+    };
+}
+
+/// Deal with an [AmpEvent](../../arcam_protocol/enum.AmpEvent.html) decoded from a
+/// [Response](../../arcam_protocol/struct.Response.html) received from the amplifier.
+fn handle_event(control_window: &Rc<ControlWindow>, event: &AmpEvent) {
+    match event {
+        AmpEvent::Power { zone, state } => control_window.set_power_display(*zone, *state),
+        AmpEvent::DisplayBrightness(level) => control_window.set_brightness_display(*level),
+        AmpEvent::Volume { zone, level } => control_window.set_volume_display(*zone, *level),
+        AmpEvent::Mute { zone, state } => control_window.set_mute_display(*zone, *state),
+        AmpEvent::DABStation { zone, name } => {
+            debug!("handle_event:  Got the station name: {}.", name);
+            control_window.set_radio_station_display(*zone, name);
         },
-        Command::RequestMuteStatus => {
-            assert_eq!(response.data.len(), 1);
-            control_window.set_mute_display(response.zone, FromPrimitive::from_u8(response.data[0]).unwrap());
+        AmpEvent::ProgrammeType { zone, category } => {
+            debug!("handle_event:  Got the station type: {}.", category);
+            control_window.set_music_type_display(*zone, category);
         },
-        Command::RequestDABStation => {
-            assert_eq!(response.data.len(), 16);
-            let message = match String::from_utf8(response.data.clone()) {
-                Ok(s) => s.trim().to_string(),
-                Err(e) => { debug!("Failed to process {:?} – {:?}", &response.data, e); "".to_string() },
-            };
-            debug!("Got the station name: {}", message);
-            control_window.set_radio_station_display(response.zone, &message);
-        }
-        Command::ProgrammeTypeCategory => {
-            assert_eq!(response.data.len(), 16);
-            let message = match String::from_utf8(response.data.clone()) {
-                Ok(s) => s.trim().to_string(),
-                Err(e) => { debug!("handle_response:  Failed to process {:?} – {:?}.", &response.data, e); "".to_string()},
-            };
-            debug!("handle_response:  Got the station type: {}.", message);
-            control_window.set_music_type_display(response.zone, &message);
-        }
-        Command::DLSPDTInformation => {
-            // An AVR850 appears to behave differently to the documentation. Documentation
-            // says a 128 byte buffer with the string padded out with spaces. Reality indicates
-            // a 129 byte buffer with a nul terminated string padded out with spaces. Implement
-            // what the real AVR850 pumps out.
-            assert_eq!(response.data.len(), 129);
-            let index_of_nul = match response.data.iter().position(|x| *x == 0u8) {
-                Some(i) => i,
-                None => { debug!("handle_response:  Failed to find a nul character in the array."); 129 },
-            };
-            let message = match String::from_utf8(response.data[0..index_of_nul].to_vec()) {
-                Ok(s) => s.trim().to_string(),
-                Err(e) => { debug!("handle_response:  Failed to get a string – {}.", e); "".to_string() },
-            };
-            debug!("functionality::handle_response: got the DLS/PDT: {}.", message);
-            control_window.set_dlspdt_information(response.zone, &message);
-        }
-        Command::RequestCurrentSource => {
-            assert_eq!(response.data.len(), 1);
-            control_window.set_source_display(response.zone, FromPrimitive::from_u8(response.data[0]).unwrap());
-        },
-        Command::SimulateRC5IRCommand => {
-            // Responses to this Request Command provide no data on the state of the
-            // amplifier, they just give the AnswerCode to the Request.
-            assert_eq!(response.data.len(), 2);
-            debug!("handle_response:  Got response for RC5 command {:?}.", RC5Command::from(&response.data));
+        AmpEvent::DlsPdt { zone, text } => {
+            debug!("handle_event:  Got the DLS/PDT: {}.", text);
+            control_window.set_dlspdt_information(*zone, text);
         },
-        x => debug!("handle_response:  Failed to deal with command {:?}.", x),
+        AmpEvent::CurrentSource { zone, source } => control_window.set_source_display(*zone, *source),
+        AmpEvent::IncomingAudioFormat { format, channels } => control_window.set_audio_format_display(*format, *channels),
+        AmpEvent::IncomingAudioSampleRate(sample_rate) => control_window.set_audio_sample_rate_display(*sample_rate),
+        AmpEvent::IncomingVideoParameters(parameters) => control_window.set_video_parameters_display(*parameters),
+        AmpEvent::SoftwareVersion(version) => control_window.set_software_version(*version),
+        AmpEvent::RC5Command { command, .. } => debug!("handle_event:  Got response for RC5 command {:?}.", command),
+        x => debug!("handle_event:  No UI action for {:?}.", x),
     };
-    control_window.set_connect_display(ConnectedState::Connected);
 }
 
-/// Attempt to extract a [Response](../arcam_protocol/struct.Response.html) packet from the
-/// queue of bytes received from the amplifier.
+/// Deal with bytes received from the amplifier.
 ///
-/// On a successful parse the bytes of the packet are removed from the queue and the (not
-/// public) [handle_response](fn.handle_response.html) function is called to implement any
-/// changes to the UI consequent on the data in the
-/// [Response](../arcam_protocol/struct.Response.html).
-pub fn try_parse_of_response_data(control_window: &Rc<ControlWindow>, queue: &mut Vec<u8>) -> bool {
-    debug!("try_parse_of_response_data:  Starting parse on queue: {:?}.", &queue);
-    match Response::parse_bytes(&queue) {
-        Ok((response, count)) => {
-            debug!("try_parse_of_response_data:  Got a successful parse of a packet – {:?}.", response);
-            for _ in 0..count { queue.remove(0); }
-            debug!("try_parse_of_response_data:  Updated buffer – {:?}.", queue);
-            handle_response(control_window, &response);
-            true
-        },
-        Err(e) => {
-            debug!("try_parse_of_response_data:  Failed to parse a packet from {:?}.", queue);
-            match e {
-                "Insufficient bytes to form a packet." => {},
-                _ => panic!("try_parse_of_response_data:  {}", e),
-            };
-            false
-        },
+/// The bytes are added to the [PacketDecoder](../../arcam_protocol/struct.PacketDecoder.html)
+/// and each complete [Response](../../arcam_protocol/struct.Response.html) packet is passed to the
+/// (not public) [handle_response](fn.handle_response.html) function to implement any changes to
+/// the UI consequent on the data in the [Response](../../arcam_protocol/struct.Response.html).
+pub fn handle_response_data(control_window: &Rc<ControlWindow>, decoder: &mut PacketDecoder<Response>, data: &[u8]) {
+    debug!("handle_response_data:  Got data {:?}.", data);
+    decoder.set_dialect(control_window.get_model().dialect());
+    decoder.push(data);
+    for frame in decoder {
+        match frame {
+            Frame::Packet(response) => handle_response(control_window, &response),
+            Frame::Amx(message) => match message.parse::<DeviceInfo>() {
+                Ok(device) => {
+                    control_window.set_device_display(&device);
+                    match Model::from_device_info(&device) {
+                        Some(model) => control_window.set_model(model),
+                        None => debug!("handle_response_data:  Unknown model {}, assuming {}.", device.model, control_window.get_model()),
+                    }
+                },
+                Err(e) => debug!("handle_response_data:  Failed to parse AMX message {} – {}.", message, e),
+            },
+            Frame::Skipped { count, error } => debug!("handle_response_data:  Skipped {} bytes – {}.", count, error),
+        }
     }
 }
@@ -21,9 +21,11 @@
 // place where the crate level documentation is taken from.
 
 //! ArcamClient is a gtk-rs based Rust application for controlling Arcam amplifiers.
+//!
+//! The protocol used to communicate with the amplifier is provided by the
+//! [arcam_protocol](../arcam_protocol/index.html) crate, which has no GUI dependencies.
 
 pub mod about;
-pub mod arcam_protocol;
 pub mod comms_manager;
 pub mod control_window;
 pub mod functionality;
@@ -26,9 +26,13 @@ use gtk;
 
 #[cfg(not(test))]
 use env_logger;
+#[cfg(not(test))]
+use log::warn;
+
+#[cfg(not(test))]
+use arcam_protocol::amp_model::Model;
 
 mod about;
-mod arcam_protocol;
 mod comms_manager;
 mod control_window;
 mod functionality;
@@ -38,8 +42,23 @@ fn main() {
     env_logger::init();
     let application = gtk::Application::new(Some("uk.org.russel.arcamclient"), gio::ApplicationFlags::empty()).expect("Application creation failed");
     glib::set_application_name("ArcamClient");
+    // The model of amplifier can be given on the command line, otherwise it is the default
+    // until the amplifier identifies itself.
+    let model = match std::env::args().nth(1) {
+        Some(name) => match name.parse::<Model>() {
+            Ok(model) => Some(model),
+            Err(_) => {
+                warn!("main:  Unknown amplifier model {}, ignoring it.", name);
+                None
+            },
+        },
+        None => None,
+    };
     application.connect_startup(move |app| {
-        let _control_window = control_window::ControlWindow::new(&app, None);
+        let control_window = control_window::ControlWindow::new(&app, None);
+        if let Some(model) = model {
+            control_window.set_model(model);
+        }
     });
     // Get a glib-gio warning if activate is not handled.
     application.connect_activate(move |_| { });
@@ -18,26 +18,43 @@
  */
 
 // Need to start a mock AVR850.
-mod start_avr850;
+mod start_mock;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use ctor::{ctor, dtor};
 
 use gio;
 use gio::prelude::*;
 
 use futures;
-use futures::channel::mpsc::{Sender, Receiver};
+use futures::channel::mpsc::Receiver;
 use futures::StreamExt;
 
-use arcamclient::arcam_protocol::{
-    AnswerCode, Brightness, Command, RC5Command, Request, Response, Source, ZoneNumber,
-    REQUEST_QUERY,
+use arcam_protocol::{
+    AnswerCode, Brightness, Command, PacketDecoder, ProtocolError, RC5Command, Request, Response, Source, Volume,
+    ZoneNumber, REQUEST_QUERY,
     get_rc5command_data
 };
-use arcamclient::comms_manager;
+use arcam_protocol::amp_model::Model;
+use arcamclient::comms_manager::{self, ConnectionEvent, ConnectionSettings, Heartbeat, Reconnection};
 use arcamclient::functionality::{
-    get_brightness_from_amp, get_source_from_amp, send_request_bytes, set_volume_on_amp, set_source_on_amp,
+    AmpSender, Client, QueryError, QuerySettings, get_brightness_from_amp, get_source_from_amp, send_request_bytes, set_volume_on_amp, set_source_on_amp,
 };
 
-use start_avr850::PORT_NUMBER;
+use start_mock::PORT_NUMBER;
+
+#[ctor]
+fn start_mock_avr850() {
+    start_mock::start(Model::AVR850);
+}
+
+#[dtor]
+fn terminate_mock_avr850() {
+    start_mock::terminate();
+}
 
 // GTK is not thread safe and starting an application requires access to the default
 // context. This means we cannot run multiple Rust tests since they are multi-threaded.
@@ -61,30 +78,40 @@ fn communications_test() {
         };
         Continue(true)
     });
-    let mut sender = match comms_manager::connect_to_amp( &tx_from_comms_manager, "127.0.0.1", unsafe { PORT_NUMBER }) {
-        Ok(s) => s,
+    let (mut tx_event_queue, mut rx_event_queue) = futures::channel::mpsc::channel(10);
+    let (tx_connection_events, rx_connection_events) = glib::MainContext::channel(glib::source::PRIORITY_DEFAULT);
+    rx_connection_events.attach(None, move |event| {
+        match tx_event_queue.try_send(event) {
+            Ok(_) => {},
+            Err(e) => assert!(false, e),
+        };
+        Continue(true)
+    });
+    let connection = match comms_manager::connect_to_amp( &tx_from_comms_manager, &tx_connection_events, "127.0.0.1", unsafe { PORT_NUMBER }, ConnectionSettings::default()) {
+        Ok(c) => c,
         Err(e) => panic!("~~~~ communications_test: failed to connect to the mock amp – {}", e),
     };
+    let sender = AmpSender::new(connection.sender());
 
-    async fn test_code(mut sender: Sender<Vec<u8>>, mut receiver: Receiver<Vec<u8>>) {
+    async fn test_code(mut sender: AmpSender, mut receiver: Receiver<Vec<u8>>) -> Receiver<Vec<u8>> {
         // Currently there is an assumption of synchronous request/response. A real
         // AVR 850 does not provide such a guarantee, the question is whether the
         // mock AVR850 does.
 
-        get_brightness_from_amp(&mut sender);
+        get_brightness_from_amp(&mut sender, Model::AVR850);
         // TODO It seems that the following .await causes the whole to terminate. Most times, but not always.
         match receiver.next().await {
             Some(s) => assert_eq!(s, Response::new(ZoneNumber::One, Command::DisplayBrightness, AnswerCode::StatusUpdate, vec![Brightness::Level2 as u8]).unwrap().to_bytes()),
             None => assert!(false, "Failed to get a value from the response queue."),
         };
 
-        set_volume_on_amp(&mut sender, ZoneNumber::One, 20);
+        set_volume_on_amp(&mut sender, Model::AVR850, ZoneNumber::One, Volume::new(20).unwrap());
         match receiver.next().await {
             Some(s) => assert_eq!(s, Response::new(ZoneNumber::One, Command::SetRequestVolume, AnswerCode::StatusUpdate, vec![0x14]).unwrap().to_bytes()),
             None => assert!(false, "Failed to get a value from the response queue."),
         };
 
-        get_source_from_amp(&mut sender, ZoneNumber::One);
+        get_source_from_amp(&mut sender, Model::AVR850, ZoneNumber::One);
         match receiver.next().await {
             Some(s) => assert_eq!(s, Response::new(ZoneNumber::One, Command::RequestCurrentSource, AnswerCode::StatusUpdate, vec![Source::CD as u8]).unwrap().to_bytes()),
             None => assert!(false, "Failed to get a value from the response queue."),
@@ -116,9 +143,9 @@ fn communications_test() {
         };
 
         // Set Zone 2 to CD and then to FollowZone1
-        set_source_on_amp(&mut sender, ZoneNumber::Two, Source::CD);
-        set_source_on_amp(&mut sender, ZoneNumber::Two, Source::FollowZone1);
-        let rc5_command = get_rc5command_data(RC5Command::CD);
+        set_source_on_amp(&mut sender, Model::AVR850, ZoneNumber::Two, Source::CD);
+        set_source_on_amp(&mut sender, Model::AVR850, ZoneNumber::Two, Source::FollowZone1);
+        let rc5_command = get_rc5command_data(RC5Command::Zone2CD);
         let rc5_data = vec![rc5_command.0, rc5_command.1];
         let expected_1 = Response::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, AnswerCode::StatusUpdate, rc5_data).unwrap().to_bytes();
         let expected_2 = Response::new(ZoneNumber::Two, Command::RequestCurrentSource, AnswerCode::StatusUpdate, vec![Source::CD as u8]).unwrap().to_bytes();
@@ -141,8 +168,160 @@ fn communications_test() {
                 None => assert!(false, "Read of responses failed."),
             }
         }
+        receiver
+    }
+
+    async fn expect_events(receiver: &mut Receiver<ConnectionEvent>, expected: &[ConnectionEvent]) {
+        for event in expected {
+            match receiver.next().await {
+                Some(e) => assert_eq!(&e, event),
+                None => assert!(false, "Failed to get a connection event."),
+            }
+        }
+    }
+
+    // Skip events, the number of addresses tried depends on the resolver, until the one wanted.
+    async fn wait_for_event(receiver: &mut Receiver<ConnectionEvent>, expected: ConnectionEvent) {
+        while let Some(event) = receiver.next().await {
+            if event == expected { return; }
+            assert!(!event.ends_connection(), "Got {:?} rather than {:?}.", event, expected);
+        }
+        assert!(false, "Failed to get a connection event.");
+    }
+
+    let receiver = context.block_on(test_code(sender, rx_queue));
+    context.block_on(expect_events(&mut rx_event_queue, &[ConnectionEvent::Resolving, ConnectionEvent::Connecting, ConnectionEvent::Connected]));
+
+    // Closing the connection stops anything more being sent on it.
+    let mut old_sender = connection.sender();
+    comms_manager::disconnect_from_amp(connection);
+    assert!(old_sender.is_closed());
+    assert!(old_sender.try_send(vec![]).is_err());
+    context.block_on(expect_events(&mut rx_event_queue, &[ConnectionEvent::Closed]));
+
+    // Reconnecting, using a different address for the same mock amp, works.
+    let connection = match comms_manager::connect_to_amp( &tx_from_comms_manager, &tx_connection_events, "localhost", unsafe { PORT_NUMBER }, ConnectionSettings::default()) {
+        Ok(c) => c,
+        Err(e) => panic!("~~~~ communications_test: failed to reconnect to the mock amp – {}", e),
+    };
+
+    async fn reconnect_test_code(mut sender: AmpSender, mut receiver: Receiver<Vec<u8>>) -> Receiver<Vec<u8>> {
+        get_brightness_from_amp(&mut sender, Model::AVR850);
+        match receiver.next().await {
+            Some(s) => assert_eq!(s, Response::new(ZoneNumber::One, Command::DisplayBrightness, AnswerCode::StatusUpdate, vec![Brightness::Level2 as u8]).unwrap().to_bytes()),
+            None => assert!(false, "Failed to get a value from the response queue after reconnecting."),
+        };
+        receiver
     }
 
-    context.block_on(test_code(sender, rx_queue));
+    let receiver = context.block_on(reconnect_test_code(AmpSender::new(connection.sender()), receiver));
+    comms_manager::disconnect_from_amp(connection);
+    context.block_on(wait_for_event(&mut rx_event_queue, ConnectionEvent::Closed));
+
+    // A connection to a port with nothing listening fails, saying why.
+    let _connection = match comms_manager::connect_to_amp( &tx_from_comms_manager, &tx_connection_events, "127.0.0.1", 1, ConnectionSettings::default()) {
+        Ok(c) => c,
+        Err(e) => panic!("~~~~ communications_test: failed to start connecting – {}", e),
+    };
+    context.block_on(wait_for_event(&mut rx_event_queue, ConnectionEvent::Failed("connection refused".to_string())));
+
+    // A connection dropped by the amplifier rebooting is reconnected, if asked for.
+    let settings = ConnectionSettings {
+        reconnection: Some(Reconnection { initial_delay: Duration::from_millis(100), maximum_delay: Duration::from_secs(1) }),
+        ..ConnectionSettings::default()
+    };
+    let connection = match comms_manager::connect_to_amp( &tx_from_comms_manager, &tx_connection_events, "127.0.0.1", unsafe { PORT_NUMBER }, settings) {
+        Ok(c) => c,
+        Err(e) => panic!("~~~~ communications_test: failed to connect to the mock amp – {}", e),
+    };
+    context.block_on(expect_events(&mut rx_event_queue, &[ConnectionEvent::Resolving, ConnectionEvent::Connecting, ConnectionEvent::Connected]));
+    let reboot = Request::new(ZoneNumber::One, Command::Reboot, b"REBOOT".to_vec()).unwrap();
+    send_request_bytes(&mut AmpSender::new(connection.sender()), &reboot.to_bytes());
+    context.block_on(expect_events(&mut rx_event_queue, &[
+        ConnectionEvent::Reconnecting { reason: "closed by the amplifier".to_string(), delay: Duration::from_millis(100) },
+        ConnectionEvent::Resolving,
+        ConnectionEvent::Connecting,
+        ConnectionEvent::Connected,
+    ]));
+    context.block_on(async move {
+        let mut receiver = receiver;
+        let expected = Response::new(ZoneNumber::One, Command::Reboot, AnswerCode::StatusUpdate, b"REBOOT".to_vec()).unwrap().to_bytes();
+        match receiver.next().await {
+            Some(s) => assert_eq!(s, expected),
+            None => assert!(false, "Failed to get the response to the reboot."),
+        };
+        reconnect_test_code(AmpSender::new(connection.sender()), receiver).await;
+    });
+    context.block_on(wait_for_event(&mut rx_event_queue, ConnectionEvent::Closed));
+
+    // Queries are answered, refused before sending if the amplifier cannot answer them, or
+    // asked again and then given up on if the amplifier does not answer.
+    let (tx_to_client, rx_to_client) = glib::MainContext::channel(glib::source::PRIORITY_DEFAULT);
+    let connection = match comms_manager::connect_to_amp( &tx_to_client, &tx_connection_events, "127.0.0.1", unsafe { PORT_NUMBER }, ConnectionSettings::default()) {
+        Ok(c) => c,
+        Err(e) => panic!("~~~~ communications_test: failed to connect to the mock amp – {}", e),
+    };
+    context.block_on(expect_events(&mut rx_event_queue, &[ConnectionEvent::Resolving, ConnectionEvent::Connecting, ConnectionEvent::Connected]));
+    let client = Client::new(connection.sender(), Model::AVR850, QuerySettings { timeout: Duration::from_millis(500), retries: 1 });
+    rx_to_client.attach(None, {
+        let client = client.clone();
+        let mut decoder = PacketDecoder::new();
+        move |data| {
+            client.handle_response_data(&mut decoder, &data);
+            Continue(true)
+        }
+    });
+    context.block_on(async {
+        assert_eq!(
+            client.query(ZoneNumber::One, Command::DisplayBrightness).await,
+            Ok(Response::new(ZoneNumber::One, Command::DisplayBrightness, AnswerCode::StatusUpdate, vec![Brightness::Level2 as u8]).unwrap()));
+        assert_eq!(
+            client.query(ZoneNumber::Two, Command::DisplayBrightness).await,
+            Err(QueryError::Protocol(ProtocolError::CommandNotForZone(Command::DisplayBrightness, ZoneNumber::Two))));
+        assert_eq!(client.query(ZoneNumber::One, Command::TrebleEqualisation).await, Err(QueryError::NoAnswer { attempts: 2 }));
+    });
+    comms_manager::disconnect_from_amp(connection);
+    context.block_on(wait_for_event(&mut rx_event_queue, ConnectionEvent::Closed));
+
+    // An idle connection is kept alive by an answered heartbeat, even one set after connecting.
+    let heartbeats_answered = Rc::new(Cell::new(0));
+    let (tx_heartbeats, rx_heartbeats) = glib::MainContext::channel(glib::source::PRIORITY_DEFAULT);
+    rx_heartbeats.attach(None, {
+        let heartbeats_answered = heartbeats_answered.clone();
+        let expected = Response::new(ZoneNumber::One, Command::Heartbeat, AnswerCode::StatusUpdate, vec![0x00]).unwrap().to_bytes();
+        move |datum: Vec<u8>| {
+            assert_eq!(datum.len() % expected.len(), 0, "Got {:?} rather than heartbeat answers.", datum);
+            heartbeats_answered.set(heartbeats_answered.get() + datum.len() / expected.len());
+            Continue(true)
+        }
+    });
+    let heartbeat = Request::new(ZoneNumber::One, Command::Heartbeat, vec![REQUEST_QUERY]).unwrap().to_bytes();
+    let connection = match comms_manager::connect_to_amp( &tx_heartbeats, &tx_connection_events, "127.0.0.1", unsafe { PORT_NUMBER }, ConnectionSettings::default()) {
+        Ok(c) => c,
+        Err(e) => panic!("~~~~ communications_test: failed to connect to the mock amp – {}", e),
+    };
+    context.block_on(expect_events(&mut rx_event_queue, &[ConnectionEvent::Resolving, ConnectionEvent::Connecting, ConnectionEvent::Connected]));
+    connection.set_heartbeat(Some(Heartbeat { request: heartbeat, interval: Duration::from_millis(100), missed_limit: 2 }));
+    context.block_on(glib::timeout_future(Duration::from_millis(1000)));
+    assert!(heartbeats_answered.get() >= 2, "Only {} heartbeats answered.", heartbeats_answered.get());
+    comms_manager::disconnect_from_amp(connection);
+    context.block_on(expect_events(&mut rx_event_queue, &[ConnectionEvent::Closed]));
+
+    // A heartbeat the amplifier does not answer means the connection is lost.
+    let unanswered = Request::new(ZoneNumber::One, Command::TrebleEqualisation, vec![REQUEST_QUERY]).unwrap().to_bytes();
+    let settings = ConnectionSettings {
+        heartbeat: Some(Heartbeat { request: unanswered, interval: Duration::from_millis(100), missed_limit: 2 }),
+        ..ConnectionSettings::default()
+    };
+    let _connection = match comms_manager::connect_to_amp( &tx_heartbeats, &tx_connection_events, "127.0.0.1", unsafe { PORT_NUMBER }, settings) {
+        Ok(c) => c,
+        Err(e) => panic!("~~~~ communications_test: failed to connect to the mock amp – {}", e),
+    };
+    context.block_on(expect_events(&mut rx_event_queue, &[
+        ConnectionEvent::Resolving,
+        ConnectionEvent::Connecting,
+        ConnectionEvent::Connected,
+        ConnectionEvent::Lost("no answer to heartbeat".to_string()),
+    ]));
     context.pop_thread_default();
 }
@@ -17,49 +17,54 @@
  *  along with this program. If not, see <http://www.gnu.org/licenses/>.
  */
 
+//! Start and terminate a mock amplifier for the integration tests. Each test file calls
+//! [start](fn.start.html) from a `#[ctor]` function with the model of amplifier to mock and
+//! [terminate](fn.terminate.html) from a `#[dtor]` function.
+
 use std::process;
 use std::thread;
 use std::time;
 
-use ctor::{ctor, dtor};
-
 use rand;
 use rand::Rng;
 
-static mut MOCK_AVR850: Option<process::Child> = None;
+use arcam_protocol::amp_model::Model;
+
+static mut MOCK_AMP: Option<process::Child> = None;
 pub static mut PORT_NUMBER: u16 = 0;
 
-#[ctor]
-fn start_mock_avr850() {
+/// Start a mock amplifier of the given model on a random port, recording the port in
+/// `PORT_NUMBER`.
+pub fn start(model: Model) {
     let mut rng = rand::thread_rng();
     unsafe {
         PORT_NUMBER = rng.gen_range(50001, 65535);
     }
     match process::Command::new("cargo")
-        .args(&["run", "--bin", "mock_avr850", unsafe { &PORT_NUMBER.to_string() }])
+        .args(&["run", "--bin", "mock_avr850", unsafe { &PORT_NUMBER.to_string() }, &model.to_string()])
         .spawn() {
         Ok(m) => {
-            unsafe { MOCK_AVR850 = Some(m); }
+            unsafe { MOCK_AMP = Some(m); }
             // The server needs a moment to settle before things will work.
             thread::sleep(time::Duration::from_millis(500));
         },
-        Err(e) => panic!("====  start_mockavr850: failed to start MOCK_AVR850 – {}", e),
+        Err(e) => panic!("====  start_mock: failed to start mock {} – {}", model, e),
     }
 }
 
-#[dtor]
-fn terminate_mock_avr850() {
+/// Terminate the mock amplifier, if one was started.
+pub fn terminate() {
     unsafe {
-        match &mut MOCK_AVR850 {
+        match &mut MOCK_AMP {
             Some(m) => {
                 match m.kill() {
                     Ok(_) => {
                         match m.wait() {
                             Ok(_) => {},
-                            Err(e) => panic!("====  start_avr850: failed to wait on mock_avr850 process: {:?}", e),
+                            Err(e) => panic!("====  start_mock: failed to wait on mock_avr850 process: {:?}", e),
                         }
                     },
-                    Err(e) => panic!("====  start_avr850: failed to terminate mock_avr850 process: {:?}", e),
+                    Err(e) => panic!("====  start_mock: failed to terminate mock_avr850 process: {:?}", e),
                 }
             },
             None => {},
@@ -18,19 +18,32 @@
  */
 
 // Need to start a mock AVR850.
-mod start_avr850;
+mod start_mock;
 
 use std::time::Duration;
 
+use ctor::{ctor, dtor};
+
 use gio;
 use gio::prelude::*;
 use gtk;
 //use gtk::prelude::*;
 
-use arcamclient::arcam_protocol::{Brightness, MuteState, PowerState, Source, ZoneNumber};
+use arcam_protocol::amp_model::Model;
+use arcam_protocol::{Brightness, MuteState, PowerState, Source, Volume, ZoneNumber};
 use arcamclient::control_window::{ConnectedState, ControlWindow};
 
-use start_avr850::PORT_NUMBER;
+use start_mock::PORT_NUMBER;
+
+#[ctor]
+fn start_mock_avr850() {
+    start_mock::start(Model::AVR850);
+}
+
+#[dtor]
+fn terminate_mock_avr850() {
+    start_mock::terminate();
+}
 
 // GTK+ is not thread safe and starting an application requires access to the default
 // context. This means we cannot run multiple Rust tests since they are multi-threaded.
@@ -57,13 +70,16 @@ fn system_test() {
 
                 // Check the initial state is correct.
                 assert_eq!(c_w.get_connect_display_value(), ConnectedState::Connected);
+                assert_eq!(c_w.get_device_display_value(), "AVR850 2.0.0");
                 assert_eq!(c_w.get_brightness_display_value(), Brightness::Level2);
                 assert_eq!(c_w.get_power_display_value(ZoneNumber::One), PowerState::On);
-                assert_eq!(c_w.get_volume_display_value(ZoneNumber::One), 30);
+                assert_eq!(c_w.get_volume_display_value(ZoneNumber::One), Volume::new(30).unwrap());
                 assert_eq!(c_w.get_mute_display_value(ZoneNumber::One), MuteState::NotMuted);
                 assert_eq!(c_w.get_source_display_value(ZoneNumber::One), Source::CD);
+                assert_eq!(c_w.get_audio_format_display_value(), "PCM");
+                assert_eq!(c_w.get_video_resolution_display_value(), "");
                 assert_eq!(c_w.get_power_display_value(ZoneNumber::Two), PowerState::Standby);
-                assert_eq!(c_w.get_volume_display_value(ZoneNumber::Two), 20);
+                assert_eq!(c_w.get_volume_display_value(ZoneNumber::Two), Volume::new(20).unwrap());
                 assert_eq!(c_w.get_mute_display_value(ZoneNumber::Two), MuteState::NotMuted);
                 assert_eq!(c_w.get_source_display_value(ZoneNumber::Two), Source::FollowZone1);
 
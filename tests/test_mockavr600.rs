@@ -0,0 +1,144 @@
+/*
+ *  arcamclient —  A gtk-rs based Rust application for controlling Arcam amplifiers.
+ *
+ *  Copyright © 2020  Russel Winder
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+// Need to start a mock AVR850 with an AVR600 personality.
+mod start_mock;
+
+use std::io::{Write, Read};
+use std::net::{SocketAddr, TcpStream};
+use std::str::from_utf8;
+
+use ctor::{ctor, dtor};
+
+use arcam_protocol::amp_model::Model;
+use arcam_protocol::{
+    AnswerCode, Brightness, Command, DeviceInfo, Dialect, Frame, PacketDecoder, PictureAdjustment, Request, Response, VideoInputType,
+    ZoneNumber,
+    REQUEST_QUERY,
+    create_amx_request,
+};
+use arcamclient::functionality;
+
+#[ctor]
+fn start_mock_avr600() {
+    start_mock::start(Model::AVR600);
+}
+
+#[dtor]
+fn terminate_mock_avr600() {
+    start_mock::terminate();
+}
+
+fn connect_to_mock_avr600() -> TcpStream {
+    match TcpStream::connect(SocketAddr::from(([127, 0, 0, 1], unsafe { start_mock::PORT_NUMBER }))) {
+        Ok(stream) => stream,
+        Err(e) => panic!("Could not connect to mock AVR600: {:?}", e),
+    }
+}
+
+fn send_to_mock_avr600(mut stream: &TcpStream, data: &[u8]) {
+    match stream.write(data) {
+        Ok(count) => assert_eq!(count, data.len()),
+        Err(e) => panic!("Failed to send data: {:?}", e),
+    }
+}
+
+fn read_from_mock_avr600(mut stream: &TcpStream, buffer: &mut [u8]) -> usize {
+    match stream.read(buffer) {
+        Ok(count) => {
+            if count == 0 {
+                panic!("Zero length read.");
+            };
+            count
+        },
+        Err(e) => panic!("Failed to read data: {:?}", e),
+    }
+}
+
+fn connect_mock_avr600_send_and_receive(send_data: &[u8]) -> Vec<u8> {
+    let stream = connect_to_mock_avr600();
+    send_to_mock_avr600(&stream, send_data);
+    let mut buffer = [0u8; 4096];
+    let count = read_from_mock_avr600(&stream, &mut buffer);
+    buffer[..count].to_vec()
+}
+
+#[test]
+fn amx_value_identifies_avr600() {
+    let data = connect_mock_avr600_send_and_receive(&create_amx_request());
+    let device = from_utf8(&data).unwrap().parse::<DeviceInfo>().unwrap();
+    assert_eq!(Model::from_device_info(&device), Some(Model::AVR600));
+}
+
+#[test]
+fn heartbeat_is_answered() {
+    let heartbeat = functionality::heartbeat(Model::AVR600).unwrap();
+    let data = connect_mock_avr600_send_and_receive(&heartbeat.request);
+    let (response, _) = Response::parse_bytes_in_dialect(&data, Dialect::AVR600).unwrap();
+    assert_eq!((response.zone, response.cc, response.ac), (ZoneNumber::One, Command::Power, AnswerCode::StatusUpdate));
+}
+
+#[test]
+fn get_default_brightness() {
+    let data = connect_mock_avr600_send_and_receive(
+        &Request::new(ZoneNumber::One, Command::DisplayBrightness, vec![REQUEST_QUERY]).unwrap().to_bytes()
+    );
+    assert_eq!(
+        Response::parse_bytes_in_dialect(&data, Dialect::AVR600).unwrap(),
+        (Response::new(ZoneNumber::One, Command::DisplayBrightness, AnswerCode::StatusUpdate, vec![Brightness::Level2 as u8]).unwrap(), 7)
+    );
+}
+
+#[test]
+fn get_default_video_input_type() {
+    let data = connect_mock_avr600_send_and_receive(
+        &Request::new(ZoneNumber::One, Command::SetRequestVideoInputType, vec![REQUEST_QUERY]).unwrap().to_bytes()
+    );
+    assert_eq!(
+        Response::parse_bytes_in_dialect(&data, Dialect::AVR600).unwrap(),
+        (Response::new(ZoneNumber::One, Command::SetRequestVideoInputType, AnswerCode::StatusUpdate, vec![VideoInputType::HDMI as u8]).unwrap(), 7)
+    );
+}
+
+#[test]
+fn set_picture_colour() {
+    let colour = PictureAdjustment::new(12).unwrap();
+    let data = connect_mock_avr600_send_and_receive(
+        &Request::new(ZoneNumber::One, Command::SetRequestColour, vec![colour.encode()]).unwrap().to_bytes()
+    );
+    assert_eq!(
+        Response::parse_bytes_in_dialect(&data, Dialect::AVR600).unwrap(),
+        (Response::new(ZoneNumber::One, Command::SetRequestColour, AnswerCode::StatusUpdate, vec![colour.encode()]).unwrap(), 7)
+    );
+}
+
+#[test]
+fn avr850_only_command_is_not_a_packet() {
+    // An AVR600 does not know the Heartbeat command so treats the bytes as a non-packet message.
+    let stream = connect_to_mock_avr600();
+    send_to_mock_avr600(&stream, &Request::new(ZoneNumber::One, Command::Heartbeat, vec![REQUEST_QUERY]).unwrap().to_bytes());
+    let mut buffer = [0u8; 4096];
+    let mut decoder = PacketDecoder::<Response>::with_dialect(Dialect::AVR600);
+    let receive_count = read_from_mock_avr600(&stream, &mut buffer);
+    decoder.push(&buffer[..receive_count]);
+    match decoder.next_frame() {
+        Some(Frame::Amx(message)) => assert_eq!(message.parse::<DeviceInfo>().unwrap().model, "AVR600"),
+        x => assert!(false, "Got unexpected frame {:?}", x),
+    }
+}
@@ -18,16 +18,33 @@
  */
 
 // Need to start a mock AVR850.
-mod start_avr850;
+mod start_mock;
 
 use std::io::{Write, Read};
 use std::net::{SocketAddr, TcpStream};
 use std::str::from_utf8;
 
-use arcamclient::arcam_protocol::{AnswerCode, Command, RC5Command, Request, Response, Source, ZoneNumber, REQUEST_QUERY, get_rc5command_data, Brightness};
+use ctor::{ctor, dtor};
+
+use arcam_protocol::amp_model::Model;
+use arcam_protocol::{
+    AnswerCode, Brightness, Command, Frame, PacketDecoder, RC5Command, Request, Response, Source, ZoneNumber,
+    REQUEST_QUERY,
+    get_rc5command_data,
+};
+
+#[ctor]
+fn start_mock_avr850() {
+    start_mock::start(Model::AVR850);
+}
+
+#[dtor]
+fn terminate_mock_avr850() {
+    start_mock::terminate();
+}
 
 fn connect_to_mock_avr850() -> TcpStream {
-    match TcpStream::connect(SocketAddr::from(([127, 0, 0, 1], unsafe { start_avr850::PORT_NUMBER }))) {
+    match TcpStream::connect(SocketAddr::from(([127, 0, 0, 1], unsafe { start_mock::PORT_NUMBER }))) {
         Ok(stream) => stream,
         Err(e) => panic!("Could not connect to mock AVR850: {:?}", e),
     }
@@ -104,17 +121,15 @@ fn send_multi_packet_message() {
     let stream = connect_to_mock_avr850();
     send_to_mock_avr850(&stream, &send_data);
     let mut buffer = [0u8; 4096];
+    let mut decoder = PacketDecoder::<Response>::new();
     let mut responses: Vec<Response> = vec![];
     while responses.len() < 3 {
         let receive_count = read_from_mock_avr850(&stream, &mut buffer);
-        let mut data = &buffer[..receive_count];
-        while data.len() > 0 {
-            match Response::parse_bytes(&data) {
-                Ok((response, count)) => {
-                    data = &data[count..];
-                    responses.push(response);
-                },
-                Err(e) => assert!(false, e),
+        decoder.push(&buffer[..receive_count]);
+        for frame in &mut decoder {
+            match frame {
+                Frame::Packet(response) => responses.push(response),
+                x => assert!(false, "Got unexpected frame {:?}", x),
             }
         }
     }
@@ -130,3 +145,23 @@ fn send_multi_packet_message() {
         assert!(expected_responses.contains(response));
     }
 }
+
+#[test]
+fn resynchronise_after_garbage() {
+    let mut send_data = vec![0x00, 0x01, 0x02];
+    send_data.append(&mut Request::new(ZoneNumber::One, Command::DisplayBrightness, vec![REQUEST_QUERY]).unwrap().to_bytes());
+    let stream = connect_to_mock_avr850();
+    send_to_mock_avr850(&stream, &send_data);
+    let mut buffer = [0u8; 4096];
+    let mut decoder = PacketDecoder::<Response>::new();
+    let mut frames: Vec<Frame<Response>> = vec![];
+    while frames.len() < 2 {
+        let receive_count = read_from_mock_avr850(&stream, &mut buffer);
+        decoder.push(&buffer[..receive_count]);
+        frames.extend(&mut decoder);
+    }
+    assert_eq!(frames, vec![
+        Frame::Amx("AMXB<Device-SDKClass=Receiver><Device-Make=ARCAM><Device-Model=AVR850><Device-Revision=2.0.0>".to_string()),
+        Frame::Packet(Response::new(ZoneNumber::One, Command::DisplayBrightness, AnswerCode::StatusUpdate, vec![Brightness::Level2 as u8]).unwrap()),
+    ]);
+}
@@ -26,12 +26,14 @@ use gtk;
 use futures;
 use futures::StreamExt;
 
-use arcamclient::arcam_protocol::{
-    Command, Source, RC5Command, Request, ZoneNumber,
+use arcam_protocol::{
+    AnswerCode, Command, PacketDecoder, PowerState, Source, RC5Command, Request, Response, ZoneNumber,
     REQUEST_QUERY,
     get_rc5command_data,
 };
+use arcamclient::comms_manager::ConnectionEvent;
 use arcamclient::control_window;
+use arcamclient::functionality;
 
 // GTK+ is not thread safe and starting an application requires access to the default
 // context. This means we cannot run multiple Rust tests since they are multi-threaded.
@@ -45,18 +47,15 @@ fn ui_test() {
     let application = gtk::Application::new(Some("uk.org.winder.arcamclient.ui_test"), gio::ApplicationFlags::empty()).unwrap();
     application.connect_startup(move |app| {
         let control_window = control_window::ControlWindow::new(&app, None);
-        // Make it seem there is a connection: connect to somewhere guaranteed to fail.
-        // Use 127.0.0.2 because it is not 127.0.0.1 and yet is a loopback address.
-        // This ensures the UI state initialisation required with no attempt to use an
-        // mock AVR850.
-        control_window.set_address("127.0.0.2");
-        control_window.set_connect_chooser(true); // Won't set the display state, so…
-        control_window.set_connect_display(control_window::ConnectedState::Connected); // …set it manually.
+        // Make it seem there is a connection without making one: showing the connection
+        // state does not connect. This ensures the UI state initialisation required with
+        // no attempt to use a mock AVR850.
+        control_window.set_connect_display(&ConnectionEvent::Connected);
         // Replace the channel to the comms manager with one that we can use for checking
         // the data sent. This cuts off the comms manager so that it's state no longer
         // matters for the tests.
         let (tx_queue, mut rx_queue) = futures::channel::mpsc::channel::<Vec<u8>>(10);
-        control_window.get_to_comms_manager_field().borrow_mut().replace(tx_queue);
+        control_window.get_to_comms_manager_field().borrow_mut().replace(functionality::AmpSender::new(tx_queue));
         // Set some tests going.
         glib::MainContext::default().spawn_local({
             let a = app.clone();
@@ -112,7 +111,7 @@ fn ui_test() {
 
                 // Set Zone 2 to CD and then to FollowZone1
                 c_w.set_source_chooser(ZoneNumber::Two, Source::CD);
-                let rc5_command = get_rc5command_data(RC5Command::CD);
+                let rc5_command = get_rc5command_data(RC5Command::Zone2CD);
                 let rc5_data = vec![rc5_command.0, rc5_command.1];
                 match rx_queue.next().await {
                     Some(s) => assert_eq!(s, Request::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, rc5_data).unwrap().to_bytes()),
@@ -134,6 +133,15 @@ fn ui_test() {
                     None => assert!(false, "Failed to get a value from the request queue."),
                 };
 
+                // An error answer to the change of source is shown to the user.
+                assert_eq!(c_w.get_answer_error_display_value(), None);
+                c_w.set_power_display(ZoneNumber::Two, PowerState::Standby);
+                let rc5_command = get_rc5command_data(RC5Command::SetZone2ToFollowZone1);
+                let rc5_data = vec![rc5_command.0, rc5_command.1];
+                let response = Response::new(ZoneNumber::Two, Command::SimulateRC5IRCommand, AnswerCode::CommandInvalidAtThisTime, rc5_data).unwrap();
+                functionality::handle_response_data(&c_w, &mut PacketDecoder::new(), &response.to_bytes());
+                assert_eq!(c_w.get_answer_error_display_value(), Some("Zone 2 cannot change source while in standby.".to_string()));
+
                 // Add the application quit event once there is no other event.
                 //
                 // Whilst this works locally and on GitLab, it fails on Travis-CI.